@@ -0,0 +1,203 @@
+/// Speaker positions, the value is the bit used for it in a channel mask.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Speaker {
+    FRONT_LEFT =              0x1,
+    FRONT_RIGHT =             0x2,
+    FRONT_CENTER =            0x4,
+    LOW_FREQUENCY =           0x8,
+    BACK_LEFT =               0x10,
+    BACK_RIGHT =              0x20,
+    FRONT_LEFT_OF_CENTER =    0x40,
+    FRONT_RIGHT_OF_CENTER =   0x80,
+    BACK_CENTER =             0x100,
+    SIDE_LEFT =               0x200,
+    SIDE_RIGHT =              0x400,
+    TOP_CENTER =              0x800,
+    TOP_FRONT_LEFT =          0x1000,
+    TOP_FRONT_CENTER =        0x2000,
+    TOP_FRONT_RIGHT =         0x4000,
+    TOP_BACK_LEFT =           0x8000,
+    TOP_BACK_CENTER =         0x10000,
+    TOP_BACK_RIGHT =          0x20000,
+}
+impl Speaker {
+    /// Every speaker, in the order the channels are stored.
+    pub const ALL: [Speaker; 18] = [
+        Self::FRONT_LEFT,
+        Self::FRONT_RIGHT,
+        Self::FRONT_CENTER,
+        Self::LOW_FREQUENCY,
+        Self::BACK_LEFT,
+        Self::BACK_RIGHT,
+        Self::FRONT_LEFT_OF_CENTER,
+        Self::FRONT_RIGHT_OF_CENTER,
+        Self::BACK_CENTER,
+        Self::SIDE_LEFT,
+        Self::SIDE_RIGHT,
+        Self::TOP_CENTER,
+        Self::TOP_FRONT_LEFT,
+        Self::TOP_FRONT_CENTER,
+        Self::TOP_FRONT_RIGHT,
+        Self::TOP_BACK_LEFT,
+        Self::TOP_BACK_CENTER,
+        Self::TOP_BACK_RIGHT,
+    ];
+
+    #[inline(always)]
+    pub const fn mask(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Which speaker each channel belongs to, stored as a channel mask.
+/// Channels are assigned to the speakers in the order of [`Speaker::ALL`],
+/// a mask of 0 means that the channels are not meant for any speaker in particular.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelLayout {
+    mask: u32,
+}
+impl ChannelLayout {
+    pub const MONO: Self = Self::from_speakers(&[Speaker::FRONT_CENTER]);
+
+    pub const STEREO: Self = Self::from_speakers(&[Speaker::FRONT_LEFT, Speaker::FRONT_RIGHT]);
+
+    pub const SURROUND_3_0: Self = Self::from_speakers(&[
+        Speaker::FRONT_LEFT, Speaker::FRONT_RIGHT, Speaker::FRONT_CENTER,
+    ]);
+
+    pub const QUAD: Self = Self::from_speakers(&[
+        Speaker::FRONT_LEFT, Speaker::FRONT_RIGHT,
+        Speaker::BACK_LEFT, Speaker::BACK_RIGHT,
+    ]);
+
+    pub const SURROUND_5_0: Self = Self::from_speakers(&[
+        Speaker::FRONT_LEFT, Speaker::FRONT_RIGHT, Speaker::FRONT_CENTER,
+        Speaker::BACK_LEFT, Speaker::BACK_RIGHT,
+    ]);
+
+    pub const SURROUND_5_1: Self = Self::from_speakers(&[
+        Speaker::FRONT_LEFT, Speaker::FRONT_RIGHT, Speaker::FRONT_CENTER, Speaker::LOW_FREQUENCY,
+        Speaker::BACK_LEFT, Speaker::BACK_RIGHT,
+    ]);
+
+    pub const SURROUND_6_1: Self = Self::from_speakers(&[
+        Speaker::FRONT_LEFT, Speaker::FRONT_RIGHT, Speaker::FRONT_CENTER, Speaker::LOW_FREQUENCY,
+        Speaker::BACK_CENTER, Speaker::SIDE_LEFT, Speaker::SIDE_RIGHT,
+    ]);
+
+    pub const SURROUND_7_1: Self = Self::from_speakers(&[
+        Speaker::FRONT_LEFT, Speaker::FRONT_RIGHT, Speaker::FRONT_CENTER, Speaker::LOW_FREQUENCY,
+        Speaker::BACK_LEFT, Speaker::BACK_RIGHT, Speaker::SIDE_LEFT, Speaker::SIDE_RIGHT,
+    ]);
+
+    /// Bits that are not a known speaker are kept, so they can be written back.
+    #[inline(always)]
+    pub const fn from_mask(mask: u32) -> Self {
+        Self { mask }
+    }
+
+    pub const fn from_speakers(speakers: &[Speaker]) -> Self {
+        let mut mask = 0;
+        let mut i = 0;
+        while i < speakers.len() {
+            mask |= speakers[i].mask();
+            i += 1;
+        }
+
+        Self { mask }
+    }
+
+    /// The layout most files with that many channels use, channels past 8 are not assigned to any speaker.
+    pub const fn default_for(channels: u16) -> Self {
+        match channels {
+            1 => Self::MONO,
+            2 => Self::STEREO,
+            3 => Self::SURROUND_3_0,
+            4 => Self::QUAD,
+            5 => Self::SURROUND_5_0,
+            6 => Self::SURROUND_5_1,
+            7 => Self::SURROUND_6_1,
+            8 => Self::SURROUND_7_1,
+            _ => Self::from_mask(0),
+        }
+    }
+
+    #[inline(always)]
+    pub const fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Number of speakers in the layout.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        (self.mask & Self::known_mask()).count_ones() as usize
+    }
+
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline(always)]
+    pub const fn contains(&self, speaker: Speaker) -> bool {
+        self.mask & speaker.mask() != 0
+    }
+
+    /// Speakers in the order the channels are stored.
+    pub fn speakers(&self) -> impl Iterator<Item = Speaker> {
+        let layout = *self;
+
+        Speaker::ALL.into_iter().filter(move |speaker| layout.contains(*speaker))
+    }
+
+    /// Speaker of the channel at `index`, if it is assigned to one.
+    pub fn speaker(&self, index: usize) -> Option<Speaker> {
+        self.speakers().nth(index)
+    }
+
+    const fn known_mask() -> u32 {
+        (Speaker::TOP_BACK_RIGHT.mask() << 1) - 1
+    }
+}
+impl From<u32> for ChannelLayout {
+    fn from(value: u32) -> Self {
+        Self::from_mask(value)
+    }
+}
+impl From<ChannelLayout> for u32 {
+    fn from(value: ChannelLayout) -> Self {
+        value.mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChannelLayout, Speaker};
+
+    #[test]
+    fn channel_masks() {
+        assert_eq!(ChannelLayout::SURROUND_5_1.mask(), 0x3F);
+        assert_eq!(ChannelLayout::SURROUND_5_1.len(), 6);
+        assert_eq!(ChannelLayout::SURROUND_5_1.speaker(3), Some(Speaker::LOW_FREQUENCY));
+        assert_eq!(ChannelLayout::SURROUND_7_1.mask(), 0x63F);
+
+        // Unknown bits are kept but are not speakers.
+        let layout = ChannelLayout::from_mask(0x8000_0003);
+        assert_eq!(layout.mask(), 0x8000_0003);
+        assert_eq!(layout.len(), 2);
+        assert_eq!(layout.speakers().collect::<Vec<_>>(), [Speaker::FRONT_LEFT, Speaker::FRONT_RIGHT]);
+        assert_eq!(ChannelLayout::from_mask(0x3F), ChannelLayout::SURROUND_5_1);
+    }
+
+    #[test]
+    fn default_layouts() {
+        assert_eq!(ChannelLayout::default_for(1), ChannelLayout::MONO);
+        assert_eq!(ChannelLayout::default_for(2), ChannelLayout::STEREO);
+        assert_eq!(ChannelLayout::default_for(6), ChannelLayout::SURROUND_5_1);
+        assert_eq!(ChannelLayout::default_for(8).len(), 8);
+        assert!(ChannelLayout::default_for(9).is_empty());
+        assert!(ChannelLayout::default_for(0).is_empty());
+    }
+}
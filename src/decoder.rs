@@ -1,4 +1,43 @@
-use crate::{AudioInfo, Sample};
+use crate::{error::Error, AudioInfo, Result, Sample};
+
+/// A value for every channel, in the channel order of the file.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum LgFrame<S> {
+    FULL(Vec<S>),
+    /// The audio ended before every channel had a value, this is always the last frame.
+    PARTIAL(Vec<S>),
+}
+impl<S> LgFrame<S> {
+    pub fn samples(&self) -> &[S] {
+        match self {
+            LgFrame::FULL(samples) | LgFrame::PARTIAL(samples) => samples,
+        }
+    }
+
+    pub fn into_samples(self) -> Vec<S> {
+        match self {
+            LgFrame::FULL(samples) | LgFrame::PARTIAL(samples) => samples,
+        }
+    }
+
+    pub fn is_partial(&self) -> bool {
+        matches!(self, LgFrame::PARTIAL(_))
+    }
+}
+
+/// Planar buffers need one buffer per channel.
+pub(crate) fn check_planar_buffers(buffers: usize, channels: u16) -> Result<()> {
+    if buffers != channels as usize {
+        return Err(Error::WrongFmtInfo(std::format!(
+            "There are {} buffers but {} channels!",
+            buffers,
+            channels
+        )));
+    }
+
+    Ok(())
+}
 
 pub trait LgDecoder: Sized {
     fn info(&self) -> AudioInfo;
@@ -6,12 +45,96 @@ pub trait LgDecoder: Sized {
     /// Iterator over the samples.
     /// Once you iterate over the elements, calling this again will not be on the start of 
     /// the samples, so it is recommended that you store the samples in a container if 
-    /// you need to reuse them.
+    /// you need to reuse them. Decoders over seekable sources can go back with 
+    /// [`crate::wav::LgWavDecoder::rewind`].
     fn samples<S: Sample>(&mut self) -> impl Iterator<Item = S>;
+
+    /// Fills `buffer` with the next samples, returns how many were read, 0 once the audio ended.
+    /// Faster than [`LgDecoder::samples`] for decoders that convert whole blocks.
+    fn read_samples<S: Sample>(&mut self, buffer: &mut [S]) -> Result<usize> {
+        let mut len = 0;
+        for (slot, sample) in buffer.iter_mut().zip(self.samples()) {
+            *slot = sample;
+            len += 1;
+        }
+
+        Ok(len)
+    }
+
+    /// Iterator over the sample frames, the interleaved samples grouped by channel.
+    /// Like [`LgDecoder::samples`] it continues from where the last iteration stopped.
+    /// Every frame is a new Vec, use [`LgDecoder::read_frame`] to reuse the same buffer.
+    fn frames<S: Sample>(&mut self) -> impl Iterator<Item = LgFrame<S>> {
+        let channels = (self.info().channels as usize).max(1);
+        let mut samples = self.samples();
+
+        std::iter::from_fn(move || {
+            let frame: Vec<S> = samples.by_ref().take(channels).collect();
+
+            match frame.len() {
+                0 => None,
+                len if len < channels => Some(LgFrame::PARTIAL(frame)),
+                _ => Some(LgFrame::FULL(frame)),
+            }
+        })
+    }
+
+    /// Fills `frame` with the next sample frame, it must have one sample per channel. Returns how many 
+    /// samples were read: the number of channels, less for a partial last frame, or 0 once the audio ended.
+    fn read_frame<S: Sample>(&mut self, frame: &mut [S]) -> Result<usize> {
+        if frame.len() != self.info().channels as usize {
+            return Err(Error::WrongFmtInfo(std::format!(
+                "Frame has {} samples but there are {} channels!",
+                frame.len(),
+                self.info().channels
+            )));
+        }
+
+        self.read_samples(frame)
+    }
+
+    /// Fills one buffer per channel, returns the number of frames decoded. It stops once the shortest 
+    /// buffer is full or the audio ends. A partial last frame is not counted, but its samples are written.
+    fn decode_planar<S: Sample, C: AsMut<[S]>>(&mut self, buffers: &mut [C]) -> Result<usize> {
+        check_planar_buffers(buffers.len(), self.info().channels)?;
+
+        let len = buffers.iter_mut().map(|buffer| buffer.as_mut().len()).min().unwrap_or(0);
+        let mut samples = self.samples::<S>();
+        let mut frames = 0;
+
+        'frames: while frames < len {
+            for buffer in buffers.iter_mut() {
+                match samples.next() {
+                    Some(sample) => buffer.as_mut()[frames] = sample,
+                    None => break 'frames,
+                }
+            }
+            frames += 1;
+        }
+
+        Ok(frames)
+    }
+
+    /// The rest of the samples, one Vec per channel. If the audio ends in the middle of a frame,
+    /// the first channels have one more sample than the others.
+    fn planar_samples<S: Sample>(&mut self) -> Vec<Vec<S>> {
+        let channels = (self.info().channels as usize).max(1);
+        let mut result: Vec<Vec<S>> = (0..channels).map(|_| Vec::new()).collect();
+
+        for (i, sample) in self.samples().enumerate() {
+            result[i % channels].push(sample);
+        }
+
+        result
+    }
     
     /// Duration of the audio in seconds.
     fn duration(&self) -> usize;
     
     /// Length of the samples.
     fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
\ No newline at end of file
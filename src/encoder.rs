@@ -1,9 +1,62 @@
-use crate::{AudioInfo, Result, Sample};
+use crate::{decoder::check_planar_buffers, error::Error, AudioInfo, Result, Sample};
+
+/// Frames interleaved at a time by [`LgEncoder::encode_planar`].
+const ENCODE_PLANAR_PART: usize = 4096;
 
 pub trait LgEncoder {
     fn info(&self) -> AudioInfo;
     
     fn encode_sample<S: Sample>(&mut self, sample: S) -> Result<()>;
+
+    /// Encodes every sample of `samples`, interleaved like [`LgEncoder::encode_sample`].
+    /// Faster than encoding them one by one for encoders that convert whole blocks.
+    fn encode_samples<S: Sample + Copy>(&mut self, samples: &[S]) -> Result<()> {
+        for &sample in samples {
+            self.encode_sample(sample)?;
+        }
+
+        Ok(())
+    }
+
+    /// Encodes a value for every channel, `frame` must have one sample per channel.
+    fn encode_frame<S: Sample + Copy>(&mut self, frame: &[S]) -> Result<()> {
+        if frame.len() != self.info().channels as usize {
+            return Err(Error::WrongFmtInfo(std::format!(
+                "Frame has {} samples but there are {} channels!",
+                frame.len(),
+                self.info().channels
+            )));
+        }
+
+        for &sample in frame {
+            self.encode_sample(sample)?;
+        }
+
+        Ok(())
+    }
+
+    /// Encodes one buffer per channel, interleaving them. Every buffer must have the same length.
+    fn encode_planar<S: Sample + Copy, C: AsRef<[S]>>(&mut self, buffers: &[C]) -> Result<()> {
+        check_planar_buffers(buffers.len(), self.info().channels)?;
+
+        let len = buffers.first().map_or(0, |buffer| buffer.as_ref().len());
+        if buffers.iter().any(|buffer| buffer.as_ref().len() != len) {
+            return Err(Error::WrongFmtInfo("Every channel buffer must have the same length!".to_string()));
+        }
+
+        // Interleaved in parts, so the samples are encoded in bulk without copying every buffer at once.
+        let mut samples = Vec::with_capacity(ENCODE_PLANAR_PART.min(len) * buffers.len());
+        for start in (0..len).step_by(ENCODE_PLANAR_PART) {
+            samples.clear();
+            for i in start..(start + ENCODE_PLANAR_PART).min(len) {
+                samples.extend(buffers.iter().map(|buffer| buffer.as_ref()[i]));
+            }
+
+            self.encode_samples(&samples)?;
+        }
+
+        Ok(())
+    }
     
     /// Number of samples encoded so far.
     fn encoded_samples(&self) -> usize;
@@ -11,4 +64,8 @@ pub trait LgEncoder {
     fn duration(&self) -> usize;
     
     fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
\ No newline at end of file
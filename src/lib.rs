@@ -7,15 +7,35 @@ pub mod writer;
 pub mod error;
 pub mod tools;
 pub mod wav;
+pub mod w64;
 pub mod sample;
+pub mod channel;
 pub use sample::*;
+pub use channel::*;
 
 pub type Result<T> = result::Result<T, error::Error>;
 
+/// Byte order of the values in a file.
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    #[default]
+    LITTLE_ENDIAN,
+    BIG_ENDIAN,
+}
+
 #[derive(Default, Debug, Clone, Copy)]
 pub struct AudioInfo {
     pub channels: u16,
     pub sample_rate: u32,
     pub bits_per_sample: u16,
     pub sample_type: Option<SampleType>,
+    /// When `None` the default layout for the number of channels is used.
+    pub channel_layout: Option<ChannelLayout>,
+}
+impl AudioInfo {
+    /// The channel layout, or the default one for the number of channels if there is none.
+    pub fn layout(&self) -> ChannelLayout {
+        self.channel_layout.unwrap_or(ChannelLayout::default_for(self.channels))
+    }
 }
\ No newline at end of file
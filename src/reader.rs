@@ -14,6 +14,8 @@ pub trait LgReader {
     
     fn read_le_u32(&mut self) -> Result<u32>;
 
+    fn read_le_u64(&mut self) -> Result<u64>;
+
     fn read_le_i8(&mut self) -> Result<i8>;
     
     fn read_le_i16(&mut self) -> Result<i16>;
@@ -25,6 +27,22 @@ pub trait LgReader {
     fn read_le_f32(&mut self) -> Result<f32>;
     
     fn read_le_f64(&mut self) -> Result<f64>;
+
+    fn read_be_u16(&mut self) -> Result<u16>;
+    
+    fn read_be_u32(&mut self) -> Result<u32>;
+
+    fn read_be_u64(&mut self) -> Result<u64>;
+    
+    fn read_be_i16(&mut self) -> Result<i16>;
+    
+    fn read_be_i32(&mut self) -> Result<i32>;
+
+    fn read_be_i32_24(&mut self) -> Result<i32>;
+    
+    fn read_be_f32(&mut self) -> Result<f32>;
+    
+    fn read_be_f64(&mut self) -> Result<f64>;
 }
 impl<R: io::Read> LgReader for R {
     fn read_into(&mut self, buffer: &mut [u8]) -> Result<()> {
@@ -66,6 +84,13 @@ impl<R: io::Read> LgReader for R {
         
         Ok(u32::from_le_bytes(buf))
     }
+    
+    fn read_le_u64(&mut self) -> Result<u64> {
+        let mut buf = [0; 8];
+        self.read_exact(&mut buf)?;
+        
+        Ok(u64::from_le_bytes(buf))
+    }
 
     fn read_le_i8(&mut self) -> Result<i8> {
         let mut buf = [0];
@@ -113,4 +138,154 @@ impl<R: io::Read> LgReader for R {
         
         Ok(f64::from_le_bytes(buf))
     }
-}
\ No newline at end of file
+
+    fn read_be_u16(&mut self) -> Result<u16> {
+        let mut buf = [0; 2];
+        self.read_exact(&mut buf)?;
+        
+        Ok(u16::from_be_bytes(buf))
+    }
+    
+    fn read_be_u32(&mut self) -> Result<u32> {
+        let mut buf = [0; 4];
+        self.read_exact(&mut buf)?;
+        
+        Ok(u32::from_be_bytes(buf))
+    }
+    
+    fn read_be_u64(&mut self) -> Result<u64> {
+        let mut buf = [0; 8];
+        self.read_exact(&mut buf)?;
+        
+        Ok(u64::from_be_bytes(buf))
+    }
+    
+    fn read_be_i16(&mut self) -> Result<i16> {
+        let mut buf = [0; 2];
+        self.read_exact(&mut buf)?;
+        
+        Ok(i16::from_be_bytes(buf))
+    }
+    
+    fn read_be_i32(&mut self) -> Result<i32> {
+        let mut buf = [0; 4];
+        self.read_exact(&mut buf)?;
+        
+        Ok(i32::from_be_bytes(buf))
+    }
+
+    fn read_be_i32_24(&mut self) -> Result<i32> {
+        let mut buf = [0; 3];
+        self.read_exact(&mut buf)?;
+
+        Ok(i32::from_be_bytes([
+            if buf[0] & 0x80 != 0 { 0xFF } else { 0x00 }, // Sign extend if needed
+            buf[0], 
+            buf[1], 
+            buf[2], 
+        ]))
+    }
+    
+    fn read_be_f32(&mut self) -> Result<f32> {
+        let mut buf = [0; 4];
+        self.read_exact(&mut buf)?;
+        
+        Ok(f32::from_be_bytes(buf))
+    }
+    
+    fn read_be_f64(&mut self) -> Result<f64> {
+        let mut buf = [0; 8];
+        self.read_exact(&mut buf)?;
+        
+        Ok(f64::from_be_bytes(buf))
+    }
+}
+
+/// Reads big endian values through the read_le_* methods, for code that only knows about little endian
+/// (like [`crate::Sample`]).
+pub(crate) struct BigEndian<'r, R>(pub(crate) &'r mut R);
+impl<R: LgReader> LgReader for BigEndian<'_, R> {
+    fn read_into(&mut self, buffer: &mut [u8]) -> Result<()> {
+        self.0.read_into(buffer)
+    }
+
+    fn read_next_bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        self.0.read_next_bytes()
+    }
+
+    fn skip_next_bytes<const N: usize>(&mut self) -> Result<()> {
+        self.0.skip_next_bytes::<N>()
+    }
+
+    fn read_le_u8(&mut self) -> Result<u8> {
+        self.0.read_le_u8()
+    }
+
+    fn read_le_u16(&mut self) -> Result<u16> {
+        self.0.read_be_u16()
+    }
+
+    fn read_le_u32(&mut self) -> Result<u32> {
+        self.0.read_be_u32()
+    }
+
+    fn read_le_u64(&mut self) -> Result<u64> {
+        self.0.read_be_u64()
+    }
+
+    fn read_le_i8(&mut self) -> Result<i8> {
+        self.0.read_le_i8()
+    }
+
+    fn read_le_i16(&mut self) -> Result<i16> {
+        self.0.read_be_i16()
+    }
+
+    fn read_le_i32(&mut self) -> Result<i32> {
+        self.0.read_be_i32()
+    }
+
+    fn read_le_i32_24(&mut self) -> Result<i32> {
+        self.0.read_be_i32_24()
+    }
+
+    fn read_le_f32(&mut self) -> Result<f32> {
+        self.0.read_be_f32()
+    }
+
+    fn read_le_f64(&mut self) -> Result<f64> {
+        self.0.read_be_f64()
+    }
+
+    fn read_be_u16(&mut self) -> Result<u16> {
+        self.0.read_le_u16()
+    }
+
+    fn read_be_u32(&mut self) -> Result<u32> {
+        self.0.read_le_u32()
+    }
+
+    fn read_be_u64(&mut self) -> Result<u64> {
+        self.0.read_le_u64()
+    }
+
+    fn read_be_i16(&mut self) -> Result<i16> {
+        self.0.read_le_i16()
+    }
+
+    fn read_be_i32(&mut self) -> Result<i32> {
+        self.0.read_le_i32()
+    }
+
+    fn read_be_i32_24(&mut self) -> Result<i32> {
+        self.0.read_le_i32_24()
+    }
+
+    fn read_be_f32(&mut self) -> Result<f32> {
+        self.0.read_le_f32()
+    }
+
+    fn read_be_f64(&mut self) -> Result<f64> {
+        self.0.read_le_f64()
+    }
+}
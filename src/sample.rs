@@ -1,74 +1,446 @@
+use std::io;
 use crate::{error::Error, reader::LgReader, tools, writer::LgWriter, Result};
 
+#[allow(non_camel_case_types)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SampleType {
     INT,
     FLOAT,
+    /// 8 bit A-law (ITU-T G.711), expanded to 16 bit linear samples.
+    ALAW,
+    /// 8 bit μ-law (ITU-T G.711), expanded to 16 bit linear samples.
+    MULAW,
+    /// 4 bit IMA/DVI ADPCM, decoded block by block into 16 bit linear samples.
+    IMA_ADPCM,
+    /// 4 bit Microsoft ADPCM, decoded block by block into 16 bit linear samples.
+    /// Only decoding is supported.
+    MS_ADPCM,
 }
 
+/// A type samples can be decoded into and encoded from.
+/// 
+/// Full scale maps to full scale, whatever the format of the file:
+/// - Integers of different sizes are converted with bit shifts, a 16 bit sample `x` is `x << 16` as an `i32`
+///   and an `i32` sample `x` is written as `x >> 16` in a 16 bit file, the bits that don't fit are dropped.
+/// - `u8` samples are unsigned like 8 bit WAV files, 128 is silence.
+/// - Floats go from -1.0 to 1.0. By default the normalisation is asymmetric, an integer of `N` bits is
+///   divided by 2^(N - 1), so the min value is -1.0 and 1.0 is just past the max value; floats outside the range
+///   are clipped when converted to integers. Use [`Symmetric`] to divide by 2^(N - 1) - 1 instead.
+/// - A-law, μ-law and ADPCM samples are 16 bit integers.
 pub trait Sample: Sized {
     fn read(reader: &mut impl LgReader, sample_type: SampleType, bits_per_sample: u16) -> Result<Self>;
     
     fn write(self, writer: &mut impl LgWriter, sample_type: SampleType, bits_per_sample: u16) -> Result<()>;
+
+    /// Converts the little endian samples in `bytes` into `samples`, there must be one for each sample.
+    /// By default they are read one by one, the implementations in this crate convert whole blocks.
+    fn read_slice(bytes: &[u8], sample_type: SampleType, bits_per_sample: u16, samples: &mut [Self]) -> Result<()> {
+        let mut reader = bytes;
+        for sample in samples.iter_mut() {
+            *sample = Self::read(&mut reader, sample_type, bits_per_sample)?;
+        }
+
+        Ok(())
+    }
+
+    /// Appends `samples` to `bytes` as little endian samples.
+    /// By default they are written one by one, the implementations in this crate convert whole blocks.
+    fn write_slice(samples: &[Self], sample_type: SampleType, bits_per_sample: u16, bytes: &mut Vec<u8>) -> Result<()> 
+    where Self: Copy,
+    {
+        let mut writer = io::Cursor::new(bytes);
+        writer.set_position(writer.get_ref().len() as u64);
+
+        for &sample in samples {
+            sample.write(&mut writer, sample_type, bits_per_sample)?;
+        }
+
+        Ok(())
+    }
 }
 
-impl Sample for i32 {
-    fn read(reader: &mut impl LgReader, sample_type: SampleType, bits_per_sample: u16) -> Result<Self> {
-        Ok(match (sample_type, bits_per_sample) {
-            (SampleType::INT, 8) => reader.read_le_i8()? as i32,
-            (SampleType::INT, 16) => reader.read_le_i16()? as i32,
-            (SampleType::INT, 24) => reader.read_le_i32_24()?,
-            (SampleType::INT, 32) => reader.read_le_i32()?,
-            (SampleType::FLOAT, 32) => tools::f32_to_i32(reader.read_le_f32()?),
-            (SampleType::FLOAT, 64) => tools::f64_to_i32(reader.read_le_f64()?),
+/// A float sample normalised symmetrically, an integer of `N` bits is divided by 2^(N - 1) - 1 so
+/// both the max value and the min value + 1 map to 1.0 and -1.0 (the min value is clipped to -1.0).
+/// 
+/// Use it in place of `f32` or `f64` when decoding or encoding, see [`Sample`] for the default model.
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Symmetric<F>(pub F);
 
-            _ => return Err(Error::Conversion(std::format!("{:?} with {} bits per sample is not supported for i32!", sample_type, bits_per_sample))),
-        })
+/// Converts every `N` bytes into a sample, the loop the fast paths of [`Sample::read_slice`] share.
+#[inline(always)]
+fn convert_slice<S, const N: usize>(bytes: &[u8], samples: &mut [S], convert: impl Fn([u8; N]) -> S) {
+    for (sample, chunk) in samples.iter_mut().zip(bytes.chunks_exact(N)) {
+        let mut buf = [0; N];
+        buf.copy_from_slice(chunk);
+        *sample = convert(buf);
     }
-    
-    fn write(self, writer: &mut impl LgWriter, sample_type: SampleType, bits_per_sample: u16) -> Result<()> {
-        match (sample_type, bits_per_sample) {
-            (SampleType::INT, 8) => writer.write_le_i8(self as i8),
-            (SampleType::INT, 16) => writer.write_le_i16(self as i16),
-            (SampleType::INT, 24) => writer.write_le_i32_24(self),
-            (SampleType::INT, 32) => writer.write_le_i32(self),
-            (SampleType::FLOAT, 32) => writer.write_le_f32(tools::i32_to_f32(self)),
-            (SampleType::FLOAT, 64) => writer.write_le_f64(tools::i32_to_f64(self)),
-
-            _ => return Err(Error::Conversion(std::format!("{:?} with {} bits per sample is not supported for i32!", sample_type, bits_per_sample))),           
+}
+
+/// Appends every sample as `N` bytes, the loop the fast paths of [`Sample::write_slice`] share.
+#[inline(always)]
+fn extend_slice<S: Copy, const N: usize>(samples: &[S], bytes: &mut Vec<u8>, convert: impl Fn(S) -> [u8; N]) {
+    bytes.reserve(samples.len() * N);
+    for &sample in samples {
+        bytes.extend_from_slice(&convert(sample));
+    }
+}
+
+/// Same as [`LgReader::read_le_i32_24`].
+#[inline(always)]
+fn i32_24_from_le_bytes([b0, b1, b2]: [u8; 3]) -> i32 {
+    i32::from_le_bytes([0, b0, b1, b2]) >> 8
+}
+
+/// Same as [`LgWriter::write_le_i32_24`].
+#[inline(always)]
+fn i32_24_to_le_bytes(value: i32) -> [u8; 3] {
+    let [b0, b1, b2, _] = value.to_le_bytes();
+
+    [b0, b1, b2]
+}
+
+// ------------------------- FULL SCALE --------------------------
+
+/// Conversions between a sample type and the values files store, full scale maps to full scale.
+pub(crate) trait FullScale: Copy {
+    /// `value` is a signed integer of `bits` bits.
+    fn from_int(value: i32, bits: u16) -> Self;
+
+    /// The sample as a signed integer of `bits` bits.
+    fn to_int(self, bits: u16) -> i32;
+
+    /// `value` goes from -1.0 to 1.0.
+    fn from_float(value: f64) -> Self;
+
+    fn to_float(self) -> f64;
+}
+
+/// 2^(bits - 1), the value that maps to 1.0.
+#[inline(always)]
+fn full_scale(bits: u16) -> f64 {
+    (1u64 << (bits - 1)) as f64
+}
+
+/// Moves a signed integer of `from` bits to `to` bits, the bits that don't fit are dropped.
+#[inline(always)]
+fn shift_bits(value: i64, from: u16, to: u16) -> i64 {
+    if to >= from {
+        value << (to - from)
+    }
+    else {
+        value >> (from - to)
+    }
+}
+
+macro_rules! full_scale_int {
+    ($($t:ty),*) => {$(
+        impl FullScale for $t {
+            #[inline(always)]
+            fn from_int(value: i32, bits: u16) -> Self {
+                shift_bits(value as i64, bits, <$t>::BITS as u16) as $t
+            }
+
+            #[inline(always)]
+            fn to_int(self, bits: u16) -> i32 {
+                shift_bits(self as i64, <$t>::BITS as u16, bits) as i32
+            }
+
+            #[inline(always)]
+            fn from_float(value: f64) -> Self {
+                // Saturates, so 1.0 becomes the max value.
+                (value * full_scale(<$t>::BITS as u16)).round() as $t
+            }
+
+            #[inline(always)]
+            fn to_float(self) -> f64 {
+                self as f64 / full_scale(<$t>::BITS as u16)
+            }
+        }
+    )*};
+}
+full_scale_int!(i8, i16, i32, i64);
+
+/// Unsigned like 8 bit WAV samples, 128 is silence.
+impl FullScale for u8 {
+    #[inline(always)]
+    fn from_int(value: i32, bits: u16) -> Self {
+        tools::i8_to_u8(i8::from_int(value, bits))
+    }
+
+    #[inline(always)]
+    fn to_int(self, bits: u16) -> i32 {
+        tools::u8_to_i8(self).to_int(bits)
+    }
+
+    #[inline(always)]
+    fn from_float(value: f64) -> Self {
+        tools::i8_to_u8(i8::from_float(value))
+    }
+
+    #[inline(always)]
+    fn to_float(self) -> f64 {
+        tools::u8_to_i8(self).to_float()
+    }
+}
+
+macro_rules! full_scale_float {
+    ($($t:ty),*) => {$(
+        impl FullScale for $t {
+            #[inline(always)]
+            fn from_int(value: i32, bits: u16) -> Self {
+                (value as f64 / full_scale(bits)) as $t
+            }
+
+            #[inline(always)]
+            fn to_int(self, bits: u16) -> i32 {
+                let scale = full_scale(bits);
+
+                (self as f64 * scale).round().clamp(-scale, scale - 1.0) as i32
+            }
+
+            #[inline(always)]
+            fn from_float(value: f64) -> Self {
+                value as $t
+            }
+
+            #[inline(always)]
+            fn to_float(self) -> f64 {
+                self as f64
+            }
+        }
+
+        impl FullScale for Symmetric<$t> {
+            #[inline(always)]
+            fn from_int(value: i32, bits: u16) -> Self {
+                // The min value goes a bit past -1.0.
+                Symmetric((value as f64 / (full_scale(bits) - 1.0)).max(-1.0) as $t)
+            }
+
+            #[inline(always)]
+            fn to_int(self, bits: u16) -> i32 {
+                let scale = full_scale(bits) - 1.0;
+
+                (self.0 as f64 * scale).round().clamp(-scale, scale) as i32
+            }
+
+            #[inline(always)]
+            fn from_float(value: f64) -> Self {
+                Symmetric(value as $t)
+            }
+
+            #[inline(always)]
+            fn to_float(self) -> f64 {
+                self.0 as f64
+            }
         }
+    )*};
+}
+full_scale_float!(f32, f64);
+
+fn read_full_scale<S: FullScale>(reader: &mut impl LgReader, sample_type: SampleType, bits_per_sample: u16) -> Result<S> {
+    Ok(match (sample_type, bits_per_sample) {
+        (SampleType::INT, 8) => S::from_int(reader.read_le_i8()? as i32, 8),
+        (SampleType::INT, 16) => S::from_int(reader.read_le_i16()? as i32, 16),
+        (SampleType::INT, 24) => S::from_int(reader.read_le_i32_24()?, 24),
+        (SampleType::INT, 32) => S::from_int(reader.read_le_i32()?, 32),
+        (SampleType::FLOAT, 32) => S::from_float(reader.read_le_f32()? as f64),
+        (SampleType::FLOAT, 64) => S::from_float(reader.read_le_f64()?),
+        // G.711 expands to 16 bit.
+        (SampleType::ALAW, 8) => S::from_int(tools::decode_alaw(reader.read_le_u8()?) as i32, 16),
+        (SampleType::MULAW, 8) => S::from_int(tools::decode_mulaw(reader.read_le_u8()?) as i32, 16),
+
+        _ => return Err(unsupported::<S>(sample_type, bits_per_sample)),
+    })
+}
+
+fn write_full_scale<S: FullScale>(sample: S, writer: &mut impl LgWriter, sample_type: SampleType, bits_per_sample: u16) -> Result<()> {
+    match (sample_type, bits_per_sample) {
+        (SampleType::INT, 8) => writer.write_le_i8(sample.to_int(8) as i8),
+        (SampleType::INT, 16) => writer.write_le_i16(sample.to_int(16) as i16),
+        (SampleType::INT, 24) => writer.write_le_i32_24(sample.to_int(24)),
+        (SampleType::INT, 32) => writer.write_le_i32(sample.to_int(32)),
+        (SampleType::FLOAT, 32) => writer.write_le_f32(sample.to_float() as f32),
+        (SampleType::FLOAT, 64) => writer.write_le_f64(sample.to_float()),
+        (SampleType::ALAW, 8) => writer.write_le_u8(tools::encode_alaw(sample.to_int(16) as i16)),
+        (SampleType::MULAW, 8) => writer.write_le_u8(tools::encode_mulaw(sample.to_int(16) as i16)),
+
+        _ => Err(unsupported::<S>(sample_type, bits_per_sample)),
     }
 }
 
-impl Sample for f32 {
-    fn read(reader: &mut impl LgReader, sample_type: SampleType, bits_per_sample: u16) -> Result<Self> {
-        let int_value = match (sample_type, bits_per_sample) {
-            (SampleType::INT, 8) => reader.read_le_i8()? as i32,
-            (SampleType::INT, 16) => reader.read_le_i16()? as i32,
-            (SampleType::INT, 24) => reader.read_le_i32_24()?,
-            (SampleType::INT, 32) => reader.read_le_i32()?,
-            
-            (SampleType::FLOAT, 32) => return Ok(reader.read_le_f32()?),
-            (SampleType::FLOAT, 64) => return Ok(reader.read_le_f64()? as f32),
+fn read_slice_full_scale<S: FullScale>(bytes: &[u8], sample_type: SampleType, bits_per_sample: u16, samples: &mut [S]) -> Result<()> {
+    match (sample_type, bits_per_sample) {
+        (SampleType::INT, 8) => convert_slice(bytes, samples, |[b]| S::from_int(tools::u8_to_i8(b) as i32, 8)),
+        (SampleType::INT, 16) => convert_slice(bytes, samples, |b| S::from_int(i16::from_le_bytes(b) as i32, 16)),
+        (SampleType::INT, 24) => convert_slice(bytes, samples, |b| S::from_int(i32_24_from_le_bytes(b), 24)),
+        (SampleType::INT, 32) => convert_slice(bytes, samples, |b| S::from_int(i32::from_le_bytes(b), 32)),
+        (SampleType::FLOAT, 32) => convert_slice(bytes, samples, |b| S::from_float(f32::from_le_bytes(b) as f64)),
+        (SampleType::FLOAT, 64) => convert_slice(bytes, samples, |b| S::from_float(f64::from_le_bytes(b))),
+        (SampleType::ALAW, 8) => convert_slice(bytes, samples, |[b]| S::from_int(tools::decode_alaw(b) as i32, 16)),
+        (SampleType::MULAW, 8) => convert_slice(bytes, samples, |[b]| S::from_int(tools::decode_mulaw(b) as i32, 16)),
 
-            _ => return Err(Error::Conversion(std::format!("{:?} with {} bits per sample is not supported for i32!", sample_type, bits_per_sample))),
-        };
-        
-        Ok(tools::i32_to_f32(int_value))
+        _ => return Err(unsupported::<S>(sample_type, bits_per_sample)),
     }
-    
-    fn write(self, writer: &mut impl LgWriter, sample_type: SampleType, bits_per_sample: u16) -> Result<()> {
-        let int_value = tools::f32_to_i32(self);
-
-        match (sample_type, bits_per_sample) {
-            (SampleType::INT, 8) => writer.write_le_i8(int_value as i8),
-            (SampleType::INT, 16) => writer.write_le_i16(int_value as i16),
-            (SampleType::INT, 24) => writer.write_le_i32_24(int_value),
-            (SampleType::INT, 32) => writer.write_le_i32(int_value),
-            (SampleType::FLOAT, 32) => writer.write_le_f32(self),
-            (SampleType::FLOAT, 64) => writer.write_le_f64(self as f64),
-
-            _ => return Err(Error::Conversion(std::format!("{:?} with {} bits per sample is not supported for i32!", sample_type, bits_per_sample))),           
+
+    Ok(())
+}
+
+fn write_slice_full_scale<S: FullScale>(samples: &[S], sample_type: SampleType, bits_per_sample: u16, bytes: &mut Vec<u8>) -> Result<()> {
+    match (sample_type, bits_per_sample) {
+        (SampleType::INT, 8) => extend_slice(samples, bytes, |s| [tools::i8_to_u8(s.to_int(8) as i8)]),
+        (SampleType::INT, 16) => extend_slice(samples, bytes, |s| (s.to_int(16) as i16).to_le_bytes()),
+        (SampleType::INT, 24) => extend_slice(samples, bytes, |s| i32_24_to_le_bytes(s.to_int(24))),
+        (SampleType::INT, 32) => extend_slice(samples, bytes, |s| s.to_int(32).to_le_bytes()),
+        (SampleType::FLOAT, 32) => extend_slice(samples, bytes, |s| (s.to_float() as f32).to_le_bytes()),
+        (SampleType::FLOAT, 64) => extend_slice(samples, bytes, |s| s.to_float().to_le_bytes()),
+        (SampleType::ALAW, 8) => extend_slice(samples, bytes, |s| [tools::encode_alaw(s.to_int(16) as i16)]),
+        (SampleType::MULAW, 8) => extend_slice(samples, bytes, |s| [tools::encode_mulaw(s.to_int(16) as i16)]),
+
+        _ => return Err(unsupported::<S>(sample_type, bits_per_sample)),
+    }
+
+    Ok(())
+}
+
+fn unsupported<S>(sample_type: SampleType, bits_per_sample: u16) -> Error {
+    Error::Conversion(std::format!(
+        "{:?} with {} bits per sample is not supported for {}!", 
+        sample_type, 
+        bits_per_sample, 
+        std::any::type_name::<S>()
+    ))
+}
+
+macro_rules! full_scale_sample {
+    ($($t:ty),*) => {$(
+        impl Sample for $t {
+            fn read(reader: &mut impl LgReader, sample_type: SampleType, bits_per_sample: u16) -> Result<Self> {
+                read_full_scale(reader, sample_type, bits_per_sample)
+            }
+
+            fn write(self, writer: &mut impl LgWriter, sample_type: SampleType, bits_per_sample: u16) -> Result<()> {
+                write_full_scale(self, writer, sample_type, bits_per_sample)
+            }
+
+            fn read_slice(bytes: &[u8], sample_type: SampleType, bits_per_sample: u16, samples: &mut [Self]) -> Result<()> {
+                read_slice_full_scale(bytes, sample_type, bits_per_sample, samples)
+            }
+
+            fn write_slice(samples: &[Self], sample_type: SampleType, bits_per_sample: u16, bytes: &mut Vec<u8>) -> Result<()> {
+                write_slice_full_scale(samples, sample_type, bits_per_sample, bytes)
+            }
         }
+    )*};
+}
+full_scale_sample!(i8, u8, i16, i32, i64, f32, f64, Symmetric<f32>, Symmetric<f64>);
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+    use super::{Sample, SampleType, Symmetric};
+
+    fn write<S: Sample>(sample: S, sample_type: SampleType, bits_per_sample: u16) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        sample.write(&mut bytes, sample_type, bits_per_sample).unwrap();
+
+        bytes
+    }
+
+    fn read<S: Sample>(bytes: &[u8], sample_type: SampleType, bits_per_sample: u16) -> S {
+        S::read(&mut &bytes[..], sample_type, bits_per_sample).unwrap()
+    }
+
+    /// Writes and reads `samples` one by one and in bulk, both must give the same bytes and samples back.
+    fn round_trip<S: Sample + Copy + PartialEq + Debug>(samples: &[S], sample_type: SampleType, bits_per_sample: u16) {
+        let bytes: Vec<u8> = samples.iter().flat_map(|&s| write(s, sample_type, bits_per_sample)).collect();
+
+        let mut bulk_bytes = Vec::new();
+        S::write_slice(samples, sample_type, bits_per_sample, &mut bulk_bytes).unwrap();
+        assert_eq!(bulk_bytes, bytes);
+
+        let bytes_per_sample = bits_per_sample as usize / 8;
+        let decoded: Vec<S> = bytes.chunks_exact(bytes_per_sample).map(|b| read(b, sample_type, bits_per_sample)).collect();
+        assert_eq!(decoded, samples);
+
+        let mut bulk_decoded = samples.to_vec();
+        S::read_slice(&bytes, sample_type, bits_per_sample, &mut bulk_decoded).unwrap();
+        assert_eq!(bulk_decoded, samples);
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(&[i8::MIN, -1, 0, 1, i8::MAX], SampleType::INT, 8);
+        round_trip(&[i8::MIN, -1, 0, 1, i8::MAX], SampleType::INT, 24);
+        round_trip(&[u8::MIN, 127, 128, 129, u8::MAX], SampleType::INT, 8);
+        round_trip(&[u8::MIN, 127, 128, 129, u8::MAX], SampleType::INT, 16);
+        round_trip(&[i16::MIN, -1, 0, 1, i16::MAX], SampleType::INT, 16);
+        round_trip(&[i16::MIN, -1, 0, 1, i16::MAX], SampleType::FLOAT, 32);
+        round_trip(&[i64::MIN, i64::MAX & !0xFFFF_FFFF, 0], SampleType::INT, 32);
+        round_trip(&[-1.0f64, -0.25, 0.0, 0.1, 0.75], SampleType::FLOAT, 64);
+        round_trip(&[-1.0f64, -0.5, 0.0, 0.5], SampleType::INT, 16);
+    }
+
+    #[test]
+    fn eight_bit_extremes() {
+        // 8 bit WAV samples are unsigned.
+        assert_eq!(write(i8::MIN, SampleType::INT, 8), [0]);
+        assert_eq!(write(i8::MAX, SampleType::INT, 8), [255]);
+        assert_eq!(read::<i8>(&[0], SampleType::INT, 8), i8::MIN);
+        assert_eq!(write(i8::MIN, SampleType::INT, 16), i16::MIN.to_le_bytes());
+
+        // The u8 midpoint is silence.
+        assert_eq!(write(128u8, SampleType::INT, 8), [128]);
+        assert_eq!(write(128u8, SampleType::INT, 16), [0, 0]);
+        assert_eq!(write(0u8, SampleType::INT, 16), i16::MIN.to_le_bytes());
+        assert_eq!(read::<u8>(&[0, 0], SampleType::INT, 16), 128);
+        assert_eq!(read::<u8>(&i16::MIN.to_le_bytes(), SampleType::INT, 16), 0);
+        assert_eq!(read::<f32>(&[128], SampleType::INT, 8), 0.0);
+        assert_eq!(read::<f32>(&[0], SampleType::INT, 8), -1.0);
+    }
+
+    #[test]
+    fn wide_types() {
+        assert_eq!(read::<i64>(&1i16.to_le_bytes(), SampleType::INT, 16), 1 << 48);
+        assert_eq!(write(i64::MIN, SampleType::INT, 16), i16::MIN.to_le_bytes());
+        assert_eq!(read::<f64>(&i32::MIN.to_le_bytes(), SampleType::INT, 32), -1.0);
+        assert_eq!(read::<f64>(&0.5f32.to_le_bytes(), SampleType::FLOAT, 32), 0.5);
     }
-}
\ No newline at end of file
+
+    /// Bytes of a 24 bit sample.
+    fn int_24(value: i32) -> Vec<u8> {
+        value.to_le_bytes()[..3].to_vec()
+    }
+
+    #[test]
+    fn integer_scaling() {
+        assert_eq!(write(0x1234i16, SampleType::INT, 24), int_24(0x123400));
+        assert_eq!(write(0x1234_5678i32, SampleType::INT, 16), 0x1234i16.to_le_bytes());
+        assert_eq!(write(i32::MIN, SampleType::INT, 24), int_24(-0x800000));
+
+        assert_eq!(read::<i16>(&int_24(0x123456), SampleType::INT, 24), 0x1234);
+        assert_eq!(read::<i32>(&int_24(-1), SampleType::INT, 24), -0x100);
+        assert_eq!(read::<i32>(&i16::MAX.to_le_bytes(), SampleType::INT, 16), 0x7FFF_0000);
+        assert_eq!(read::<i8>(&i16::MIN.to_le_bytes(), SampleType::INT, 16), i8::MIN);
+    }
+
+    #[test]
+    fn float_full_scale() {
+        // Asymmetric: the min value is -1.0 and 1.0 is clipped to the max value.
+        assert_eq!(write(1.0f32, SampleType::INT, 16), i16::MAX.to_le_bytes());
+        assert_eq!(write(-1.0f32, SampleType::INT, 16), i16::MIN.to_le_bytes());
+        assert_eq!(write(2.0f32, SampleType::INT, 24), int_24(0x7FFFFF));
+        assert_eq!(read::<f32>(&i16::MIN.to_le_bytes(), SampleType::INT, 16), -1.0);
+        assert_eq!(read::<f32>(&i16::MAX.to_le_bytes(), SampleType::INT, 16), 32767.0 / 32768.0);
+        assert_eq!(write(1.0f32, SampleType::FLOAT, 32), 1.0f32.to_le_bytes());
+        assert_eq!(read::<i32>(&1.0f32.to_le_bytes(), SampleType::FLOAT, 32), i32::MAX);
+        assert_eq!(read::<i32>(&(-1.0f32).to_le_bytes(), SampleType::FLOAT, 32), i32::MIN);
+
+        // Symmetric: both max values are 1.0 and -1.0, the min value is clipped to -1.0.
+        assert_eq!(write(Symmetric(1.0f32), SampleType::INT, 16), i16::MAX.to_le_bytes());
+        assert_eq!(write(Symmetric(-1.0f32), SampleType::INT, 16), (-i16::MAX).to_le_bytes());
+        assert_eq!(write(Symmetric(-2.0f32), SampleType::INT, 24), int_24(-0x7FFFFF));
+        assert_eq!(read::<Symmetric<f32>>(&i16::MAX.to_le_bytes(), SampleType::INT, 16), Symmetric(1.0));
+        assert_eq!(read::<Symmetric<f32>>(&(-i16::MAX).to_le_bytes(), SampleType::INT, 16), Symmetric(-1.0));
+        assert_eq!(read::<Symmetric<f64>>(&i16::MIN.to_le_bytes(), SampleType::INT, 16), Symmetric(-1.0));
+    }
+}
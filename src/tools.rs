@@ -1,3 +1,5 @@
+use crate::sample::FullScale;
+
 #[inline(always)]
 pub fn u8_to_i8(value: u8) -> i8 {
     (value as i16 - 128) as i8
@@ -8,60 +10,159 @@ pub fn i8_to_u8(value: i8) -> u8 {
     (value as i16 + 128) as u8
 }
 
+/// The same full scale conversion [`crate::Sample`] uses, 1.0 saturates to i32::MAX.
 #[inline(always)]
 pub fn f32_to_i32(value: f32) -> i32 {
-    (value * (i32::MAX as f32)) as i32
+    i32::from_float(value as f64)
 }
 
+/// The same full scale conversion [`crate::Sample`] uses, i32::MIN is -1.0.
 #[inline(always)]
 pub fn i32_to_f32(value: i32) -> f32 {
-    (value as f32) / i32::MAX as f32
+    value.to_float() as f32
 }
 
+/// See [`f32_to_i32`].
 #[inline(always)] 
 pub fn f64_to_i32(value: f64) -> i32 {
-    (value * (i32::MAX as f64)) as i32
+    i32::from_float(value)
 }
 
+/// See [`i32_to_f32`].
 #[inline(always)]
 pub fn i32_to_f64(value: i32) -> f64 {
-    (value as f64) / i32::MAX as f64
+    value.to_float()
 }
 
-#[allow(unused)]
-fn encode_alaw(value: i16) -> u8 {
-    todo!();
-    let sign_bit = if value < 0 { 0x80 } else { 0 };
-    let abs_sample = if value < 0 { -value } else { value };
+// ------------------------- G.711 --------------------------
+const ALAW_SEG_END: [i16; 8] = [0x1F, 0x3F, 0x7F, 0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF];
 
-    let compressed_value = if abs_sample > 32635 {
-        0x7F
-    } 
-    else if abs_sample >= 256 {
-        let exponent = (abs_sample.leading_zeros() - 23) as i16;
-        let mantissa = (abs_sample >> (exponent + 3)) & 0x0F;
-        ((exponent << 4) | mantissa) as u8
-    } 
+/// Compresses a 16 bit linear sample into an 8 bit A-law value (ITU-T G.711).
+pub fn encode_alaw(value: i16) -> u8 {
+    // A-law works with 13 bit magnitudes.
+    let mut pcm = value >> 3;
+    let mask = if pcm >= 0 {
+        0xD5
+    }
     else {
-        (abs_sample >> 4) as u8
+        pcm = -pcm - 1;
+        0x55
     };
 
-    !((compressed_value | sign_bit) as u8)
+    let seg = ALAW_SEG_END.iter()
+        .position(|&end| pcm <= end)
+        .unwrap_or(ALAW_SEG_END.len());
+
+    if seg >= ALAW_SEG_END.len() {
+        return 0x7F ^ mask;
+    }
+    
+    let mantissa = if seg < 2 { (pcm >> 1) & 0x0F } else { (pcm >> seg) & 0x0F };
+
+    (((seg as i16) << 4) | mantissa) as u8 ^ mask
 }
 
-#[allow(unused)]
-fn decode_alaw(value: u8) -> i16 {
-    todo!();
-    let alaw_sample = !value;
-    let sign = if alaw_sample & 0x80 != 0 { -1 } else { 1 };
-    let exponent = (alaw_sample & 0x70) >> 4;
-    let mantissa = (alaw_sample & 0x0F) as i16;
-
-    let decoded_sample = if exponent == 0 {
-        (mantissa << 4) + 8
-    } else {
-        ((mantissa << 4) | 0x100) << (exponent - 1)
+/// Expands an 8 bit A-law value (ITU-T G.711) into a 16 bit linear sample.
+pub fn decode_alaw(value: u8) -> i16 {
+    let value = value ^ 0x55;
+    let seg = (value & 0x70) >> 4;
+    let mut decoded = ((value & 0x0F) as i16) << 4;
+
+    match seg {
+        0 => decoded += 8,
+        1 => decoded += 0x108,
+        _ => decoded = (decoded + 0x108) << (seg - 1),
+    }
+
+    if value & 0x80 != 0 { decoded } else { -decoded }
+}
+
+const MULAW_SEG_END: [i16; 8] = [0x3F, 0x7F, 0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF, 0x1FFF];
+const MULAW_BIAS: i16 = 0x84;
+const MULAW_CLIP: i16 = 8159;
+
+/// Compresses a 16 bit linear sample into an 8 bit μ-law value (ITU-T G.711).
+pub fn encode_mulaw(value: i16) -> u8 {
+    // μ-law works with 14 bit magnitudes.
+    let mut pcm = value >> 2;
+    let mask = if pcm < 0 {
+        pcm = -pcm;
+        0x7F
+    }
+    else {
+        0xFF
     };
 
-    sign * decoded_sample
-}
\ No newline at end of file
+    pcm = pcm.min(MULAW_CLIP) + (MULAW_BIAS >> 2);
+
+    let seg = MULAW_SEG_END.iter()
+        .position(|&end| pcm <= end)
+        .unwrap_or(MULAW_SEG_END.len());
+
+    if seg >= MULAW_SEG_END.len() {
+        return 0x7F ^ mask;
+    }
+    
+    (((seg as i16) << 4) | ((pcm >> (seg + 1)) & 0x0F)) as u8 ^ mask
+}
+
+/// Expands an 8 bit μ-law value (ITU-T G.711) into a 16 bit linear sample.
+pub fn decode_mulaw(value: u8) -> i16 {
+    let value = !value;
+    let decoded = ((((value & 0x0F) as i16) << 3) + MULAW_BIAS) << ((value & 0x70) >> 4);
+
+    if value & 0x80 != 0 { MULAW_BIAS - decoded } else { decoded - MULAW_BIAS }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_alaw, decode_mulaw, encode_alaw, encode_mulaw, f32_to_i32, f64_to_i32, i32_to_f32, i32_to_f64};
+
+    #[test]
+    fn full_scale_helpers() {
+        assert_eq!(f32_to_i32(0.5), 1 << 30);
+        assert_eq!(f32_to_i32(-1.0), i32::MIN);
+        assert_eq!(f32_to_i32(1.0), i32::MAX);
+        assert_eq!(f64_to_i32(-0.25), -(1 << 29));
+
+        assert_eq!(i32_to_f32(1 << 30), 0.5);
+        assert_eq!(i32_to_f32(i32::MIN), -1.0);
+        assert_eq!(i32_to_f64(-(1 << 29)), -0.25);
+        // A 16 bit sample read as i32, like the decoders give it.
+        assert_eq!(i32_to_f64(-16384 << 16), -0.5);
+    }
+
+    #[test]
+    fn alaw_reference_values() {
+        // Silence is 0xD5, the smallest positive step of the first segment.
+        assert_eq!(decode_alaw(0xD5), 8);
+        assert_eq!(encode_alaw(0), 0xD5);
+        assert_eq!(decode_alaw(0x55), -8);
+        assert_eq!(decode_alaw(0xAA), 32256);
+        assert_eq!(decode_alaw(0x2A), -32256);
+    }
+
+    #[test]
+    fn alaw_round_trip() {
+        for value in 0..=u8::MAX {
+            assert_eq!(encode_alaw(decode_alaw(value)), value, "{value:#04X}");
+        }
+    }
+
+    #[test]
+    fn mulaw_reference_values() {
+        assert_eq!(decode_mulaw(0xFF), 0);
+        assert_eq!(decode_mulaw(0x00), -32124);
+        assert_eq!(decode_mulaw(0x80), 32124);
+        assert_eq!(encode_mulaw(0), 0xFF);
+    }
+
+    #[test]
+    fn mulaw_round_trip() {
+        for value in 0..=u8::MAX {
+            // 0x7F is the negative zero, it comes back as the positive one.
+            let expected = if value == 0x7F { 0xFF } else { value };
+            assert_eq!(encode_mulaw(decode_mulaw(value)), expected, "{value:#04X}");
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::{fmt, fs, io, path};
+use crate::{decoder::{check_planar_buffers, LgDecoder}, error::Error, AudioInfo, ByteOrder, Result, SampleType};
+use crate::wav::{adpcm::AdpcmBlocks, LgWavBulkReader, LgWavSampleIter};
+use super::{reader::LgW64Reader, W64Chunks};
+
+/// Decodes Sony Wave64 files, the formats are the same ones [`crate::wav::LgWavDecoder`] supports.
+pub struct LgW64Decoder<R: io::Read> {
+    info: AudioInfo,
+    sample_len: usize,
+
+    reader: LgW64Reader<R>,
+    /// Only present for block based formats (ADPCM).
+    blocks: Option<AdpcmBlocks>,
+    bulk: LgWavBulkReader,
+}
+impl<R: io::Read> fmt::Debug for LgW64Decoder<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LgW64Decoder")
+            .field("info", &self.info)
+            .field("sample_len", &self.sample_len)
+            .finish()
+    }
+}
+impl LgW64Decoder<io::BufReader<fs::File>> {
+    pub fn new(path: impl AsRef<path::Path>) -> Result<Self> {
+        let file = fs::File::open(path)?;
+
+        Self::from_seekable(io::BufReader::new(file))
+    }
+}
+impl<R: io::Read> LgW64Decoder<R> {
+    /// Decodes from any reader, like stdin or a `&[u8]`.
+    pub fn from_reader(reader: R) -> Result<Self> {
+        // Already checks the header.
+        let mut reader = LgW64Reader::new(reader)?;
+
+        // Just in case the fmt chunk is not present.
+        let mut fmt = Err(Error::WrongFmt);
+        let mut fact_sample_frames = None;
+
+        loop {
+            match reader.read_next_chunk()? {
+                W64Chunks::FMT(w64_fmt) => fmt = Ok(w64_fmt),
+                W64Chunks::FACT(sample_frames) => fact_sample_frames = Some(sample_frames),
+                W64Chunks::DATA(d_len) => {
+                    let fmt = fmt?;
+                    let (sample_len, blocks) = fmt.data_samples(d_len, fact_sample_frames);
+
+                    return Ok(Self {
+                        info: fmt.info,
+                        sample_len,
+                        reader,
+                        blocks,
+                        bulk: LgWavBulkReader::new(&fmt.info, ByteOrder::LITTLE_ENDIAN),
+                    });
+                },
+                W64Chunks::UNKNOWN => (),
+            }
+        }
+    }
+}
+impl<R: io::Read + io::Seek> LgW64Decoder<R> {
+    /// The same as [`LgW64Decoder::from_reader`], the decoder does not use any chunk after the audio data.
+    /// It is here so Wave64 and WAV files are opened the same way.
+    pub fn from_seekable(reader: R) -> Result<Self> {
+        Self::from_reader(reader)
+    }
+}
+impl<R: io::Read> LgDecoder for LgW64Decoder<R> {
+    #[inline(always)]
+    fn info(&self) -> AudioInfo {
+        self.info
+    }
+
+    #[inline(always)]
+    fn samples<S: crate::Sample>(&mut self) -> impl Iterator<Item = S> {
+        let sample_type = match self.info.sample_type {
+            Some(st) => st,
+            None => SampleType::INT,
+        };
+
+        LgWavSampleIter::new(&mut self.reader.reader, self.blocks.as_mut(), sample_type, self.info.bits_per_sample, ByteOrder::LITTLE_ENDIAN)
+    }
+
+    fn read_samples<S: crate::Sample>(&mut self, buffer: &mut [S]) -> Result<usize> {
+        self.bulk.read_samples(&mut self.reader.reader, self.blocks.as_mut(), buffer)
+    }
+
+    fn decode_planar<S: crate::Sample, C: AsMut<[S]>>(&mut self, buffers: &mut [C]) -> Result<usize> {
+        check_planar_buffers(buffers.len(), self.info.channels)?;
+
+        self.bulk.decode_planar(&mut self.reader.reader, self.blocks.as_mut(), buffers)
+    }
+
+    fn planar_samples<S: crate::Sample>(&mut self) -> Vec<Vec<S>> {
+        self.bulk.planar_samples(&mut self.reader.reader, self.blocks.as_mut(), self.info.channels as usize)
+    }
+
+    #[inline(always)]
+    fn duration(&self) -> usize {
+        self.sample_len / self.info.channels as usize / self.info.sample_rate as usize
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.sample_len
+    }
+}
@@ -0,0 +1,83 @@
+use std::{fs, io, path};
+
+use crate::{encoder::LgEncoder, Result, SampleType, AudioInfo};
+use super::writer::LgW64Writer;
+
+/// Encodes Sony Wave64 files, the formats are the same ones [`crate::wav::LgWavEncoder`] supports.
+pub struct LgW64Encoder<W: io::Write + io::Seek> {
+    pub(super) info: AudioInfo,
+    writer: LgW64Writer<W>,
+}
+impl LgW64Encoder<io::BufWriter<fs::File>> {
+    pub fn new(path: impl AsRef<path::Path>, info: AudioInfo) -> Result<Self> {
+        let file = fs::File::create(path)?;
+
+        Self::from_writer(io::BufWriter::new(file), info)
+    }
+}
+impl<W: io::Write + io::Seek> LgW64Encoder<W> {
+    /// Encodes into any writer that can seek, like an `io::Cursor<Vec<u8>>` or a file that is already open.
+    /// The file starts wherever the writer is, so it can follow other data.
+    pub fn from_writer(writer: W, info: AudioInfo) -> Result<Self> {
+        let writer = LgW64Writer::new(writer, &info)?;
+
+        Ok(Self {
+            info,
+            writer,
+        })
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        self.writer.finish()
+    }
+
+    /// Finishes the file and returns the writer, positioned at the end of it.
+    pub fn into_inner(self) -> Result<W> {
+        self.writer.into_inner()
+    }
+}
+impl<W: io::Write + io::Seek> LgEncoder for LgW64Encoder<W> {
+    #[inline(always)]
+    fn info(&self) -> AudioInfo {
+        self.info
+    }
+
+    #[inline(always)]
+    fn encode_sample<S: crate::Sample>(&mut self, sample: S) -> Result<()> {
+        let sample_type = match self.info.sample_type {
+            Some(st) => st,
+            None => SampleType::INT,
+        };
+
+        self.writer.write_sample(sample, sample_type, self.info.bits_per_sample)
+    }
+
+    fn encode_samples<S: crate::Sample + Copy>(&mut self, samples: &[S]) -> Result<()> {
+        let sample_type = match self.info.sample_type {
+            Some(st) => st,
+            None => SampleType::INT,
+        };
+        let bits_per_sample = self.info.bits_per_sample;
+
+        self.writer.write_samples(samples, sample_type, bits_per_sample)
+    }
+
+    #[inline(always)]
+    fn encoded_samples(&self) -> usize {
+        self.writer.samples_written as usize
+    }
+
+    #[inline(always)]
+    fn duration(&self) -> usize {
+        self.len() / self.info.channels as usize / self.info.sample_rate as usize
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.writer.samples_written as usize
+    }
+}
@@ -0,0 +1,139 @@
+use crate::wav::WavFmt;
+
+pub mod decoder;
+pub mod encoder;
+pub mod reader;
+pub mod writer;
+
+pub use decoder::LgW64Decoder;
+pub use encoder::LgW64Encoder;
+
+// ------------------------- CHUNK IDS --------------------------
+// Sony Wave64 uses GUIDs instead of FourCCs, the ones of the RIFF chunks start with their FourCC.
+
+const RIFF_GUID: [u8; 16] = [
+    0x72, 0x69, 0x66, 0x66, 0x2E, 0x91, 0xCF, 0x11, 0xA5, 0xD6, 0x28, 0xDB, 0x04, 0xC1, 0x00, 0x00
+];
+const WAVE_GUID: [u8; 16] = [
+    0x77, 0x61, 0x76, 0x65, 0xF3, 0xAC, 0xD3, 0x11, 0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A
+];
+const FMT_GUID: [u8; 16] = [
+    0x66, 0x6D, 0x74, 0x20, 0xF3, 0xAC, 0xD3, 0x11, 0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A
+];
+const FACT_GUID: [u8; 16] = [
+    0x66, 0x61, 0x63, 0x74, 0xF3, 0xAC, 0xD3, 0x11, 0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A
+];
+const DATA_GUID: [u8; 16] = [
+    0x64, 0x61, 0x74, 0x61, 0xF3, 0xAC, 0xD3, 0x11, 0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A
+];
+
+/// GUID and the 64 bit size, the size of every chunk counts it.
+const CHUNK_HEADER_SIZE: u64 = 24;
+
+// ------------------------- CHUNKS --------------------------
+#[allow(clippy::upper_case_acronyms)]
+pub(super) enum W64Chunks {
+    FMT(WavFmt),
+    /// Number of sample frames.
+    FACT(u64),
+    /// Size of the audio data, without the chunk header.
+    DATA(u64),
+    /// Any chunk the decoder does not use, it was skipped.
+    UNKNOWN,
+}
+
+/// Chunks start at multiples of 8 bytes.
+#[inline(always)]
+fn aligned(size: u64) -> u64 {
+    (size + 7) & !7
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use crate::{decoder::LgDecoder, encoder::LgEncoder, AudioInfo, SampleType};
+    use crate::wav::fixtures::{info, samples};
+    use super::{LgW64Decoder, LgW64Encoder, RIFF_GUID, WAVE_GUID};
+
+    fn encode(info: AudioInfo, samples: &[i16]) -> Vec<u8> {
+        let mut encoder = LgW64Encoder::from_writer(io::Cursor::new(Vec::new()), info).unwrap();
+        encoder.encode_samples(samples).unwrap();
+
+        encoder.into_inner().unwrap().into_inner()
+    }
+
+    #[test]
+    fn w64_round_trip() {
+        let samples = samples(4000);
+
+        for (sample_type, bits_per_sample) in [(SampleType::INT, 16), (SampleType::INT, 24), (SampleType::FLOAT, 32)] {
+            let info = info(sample_type, bits_per_sample);
+            let file = encode(info, &samples);
+
+            assert_eq!(file[..16], RIFF_GUID);
+            assert_eq!(u64::from_le_bytes(file[16..24].try_into().unwrap()), file.len() as u64);
+            assert_eq!(file[24..40], WAVE_GUID);
+
+            let mut decoder = LgW64Decoder::from_reader(&file[..]).unwrap();
+            assert_eq!(decoder.info().bits_per_sample, bits_per_sample);
+            assert_eq!(decoder.info().sample_type, Some(sample_type));
+            assert_eq!(decoder.len(), samples.len());
+            assert_eq!(decoder.samples::<i16>().collect::<Vec<_>>(), samples);
+
+            let mut decoder = LgW64Decoder::from_seekable(io::Cursor::new(file)).unwrap();
+            let mut buffer = vec![0i16; samples.len() + 1];
+            assert_eq!(decoder.read_samples(&mut buffer).unwrap(), samples.len());
+            assert_eq!(buffer[..samples.len()], samples);
+        }
+    }
+
+    #[test]
+    fn w64_ima_round_trip() {
+        let samples: Vec<i16> = (0..4000).map(|i| ((i as f64 / 40.0).sin() * 10000.0) as i16).collect();
+        let info = AudioInfo { channels: 1, ..info(SampleType::IMA_ADPCM, 4) };
+        let file = encode(info, &samples);
+
+        let mut decoder = LgW64Decoder::from_reader(&file[..]).unwrap();
+        assert_eq!(decoder.len(), samples.len());
+
+        let decoded: Vec<i16> = decoder.samples().collect();
+        assert_eq!(decoded.len(), samples.len());
+        // The step size starts at its smallest, so the first samples are left out while it adapts.
+        assert!(samples.iter().zip(&decoded).skip(32).all(|(a, b)| (*a as i32 - *b as i32).abs() < 1000));
+    }
+
+    #[test]
+    fn w64_wrong_info() {
+        let info = AudioInfo { channels: 0, ..info(SampleType::INT, 16) };
+        let mut file = Vec::new();
+
+        assert!(LgW64Encoder::from_writer(io::Cursor::new(&mut file), info).is_err());
+        assert!(file.is_empty());
+    }
+
+    #[test]
+    fn w64_after_other_data() {
+        let samples = samples(2000);
+        let mut writer = io::Cursor::new(b"other".to_vec());
+        writer.set_position(5);
+
+        let mut encoder = LgW64Encoder::from_writer(writer, info(SampleType::INT, 16)).unwrap();
+        encoder.encode_samples(&samples).unwrap();
+        let file = encoder.into_inner().unwrap().into_inner();
+
+        assert_eq!(&file[..5], b"other");
+        assert_eq!(file[5..], encode(info(SampleType::INT, 16), &samples));
+    }
+
+    #[test]
+    fn w64_bulk_matches_one_by_one() {
+        let samples = samples(40002);
+
+        for info in [info(SampleType::INT, 24), info(SampleType::FLOAT, 64), info(SampleType::IMA_ADPCM, 4)] {
+            let mut encoder = LgW64Encoder::from_writer(io::Cursor::new(Vec::new()), info).unwrap();
+            samples.iter().for_each(|&sample| encoder.encode_sample(sample).unwrap());
+
+            assert!(encode(info, &samples) == encoder.into_inner().unwrap().into_inner());
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::io;
+use crate::error::Error;
+use crate::reader::LgReader;
+use crate::wav::WavFmt;
+use crate::{ByteOrder, Result};
+use super::{aligned, W64Chunks, CHUNK_HEADER_SIZE, DATA_GUID, FACT_GUID, FMT_GUID, RIFF_GUID, WAVE_GUID};
+
+/// riff GUID, size and wave GUID.
+const HEADER_SIZE: u64 = 40;
+
+pub struct LgW64Reader<R: io::Read> {
+    /// Limited to the riff chunk, and to the audio data once the data chunk is found.
+    pub(super) reader: io::Take<R>,
+    /// Where the reader is, from the start of the file. Not updated while reading samples.
+    position: u64,
+    /// Where the riff chunk ends, from the start of the file.
+    riff_end: u64,
+}
+impl<R: io::Read> LgW64Reader<R> {
+    pub(super) fn new(reader: R) -> Result<Self> {
+        Self::read_header(reader)
+    }
+
+    pub(super) fn read_header(mut reader: R) -> Result<Self> {
+        if RIFF_GUID != reader.read_next_bytes()? {
+            return Err(Error::WrongHeader);
+        }
+
+        // Counts the whole file, header included.
+        let riff_size = reader.read_le_u64()?;
+
+        if WAVE_GUID != reader.read_next_bytes()? || riff_size < HEADER_SIZE {
+            return Err(Error::WrongHeader);
+        }
+
+        Ok(Self {
+            reader: io::Read::take(reader, riff_size - HEADER_SIZE),
+            position: HEADER_SIZE,
+            riff_end: riff_size,
+        })
+    }
+
+    pub(super) fn read_next_chunk(&mut self) -> Result<W64Chunks> {
+        let ck_id: [u8; 16] = self.reader.read_next_bytes()?;
+        let ck_size = self.reader.read_le_u64()?
+            .checked_sub(CHUNK_HEADER_SIZE)
+            .ok_or(Error::WrongHeader)?;
+        self.position += CHUNK_HEADER_SIZE;
+
+        Ok(match ck_id {
+            FMT_GUID => W64Chunks::FMT(WavFmt::read(&self.read_chunk_data(ck_size)?, ByteOrder::LITTLE_ENDIAN)?),
+            FACT_GUID => {
+                let data = self.read_chunk_data(ck_size)?;
+
+                // Wave64 stores 64 bit sample frames, some writers still use 32 bits.
+                W64Chunks::FACT(match data.len() {
+                    0..4 => return Err(Error::WrongFmtInfo("fact chunk must have ck_size of at least 4!".to_string())),
+                    4..8 => (&data[..]).read_le_u32()? as u64,
+                    _ => (&data[..]).read_le_u64()?,
+                })
+            },
+            DATA_GUID => {
+                // Some files will have chunks after the data chunk.
+                // We don't want that to be marked as a sample, so we make sure we only read the rest of the data.
+                let data_len = ck_size.min(self.riff_end.saturating_sub(self.position));
+                self.reader.set_limit(data_len);
+
+                W64Chunks::DATA(data_len)
+            },
+            _ => {
+                self.skip_bytes(aligned(ck_size))?;
+
+                W64Chunks::UNKNOWN
+            },
+        })
+    }
+
+    /// Reads the whole chunk data, and skips the alignment.
+    fn read_chunk_data(&mut self, ck_size: u64) -> Result<Vec<u8>> {
+        if ck_size > self.reader.limit() {
+            return Err(Error::Io(io::Error::new::<String>(io::ErrorKind::UnexpectedEof, "".into())));
+        }
+
+        let mut data = vec![0; ck_size as usize];
+        self.reader.read_into(&mut data)?;
+        self.position += ck_size;
+        self.skip_bytes(aligned(ck_size) - ck_size)?;
+
+        Ok(data)
+    }
+
+    fn skip_bytes(&mut self, n: u64) -> Result<()> {
+        // The alignment of the last chunk may be missing.
+        let n = n.min(self.reader.limit());
+
+        let skipped = io::copy(&mut io::Read::take(&mut self.reader, n), &mut io::sink())?;
+        if skipped != n {
+            return Err(Error::Io(io::Error::new::<String>(io::ErrorKind::UnexpectedEof, "".into())));
+        }
+        self.position += n;
+
+        Ok(())
+    }
+}
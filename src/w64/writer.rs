@@ -0,0 +1,192 @@
+use std::io::{self, Seek, Write};
+use crate::{writer::{LgSeekWriter, LgWriter}, ByteOrder, Result, Sample, SampleType, AudioInfo};
+use crate::wav::{adpcm::ImaAdpcmWriter, fmt, writer::{write_sample_parts, Output}};
+use super::{aligned, CHUNK_HEADER_SIZE, DATA_GUID, FACT_GUID, FMT_GUID, RIFF_GUID, WAVE_GUID};
+
+const RIFF_SIZE_POSITION: usize = 16;
+
+pub struct LgW64Writer<W: io::Write + io::Seek> {
+    pub(super) writer: Output<W>,
+    /// Where the file starts in the writer, the header positions are relative to it.
+    start: usize,
+    pub(super) data_bytes_written: u64,
+    pub(super) samples_written: u64,
+    data_size_position: usize,
+    /// Position of the sample frames, only present for non PCM formats.
+    fact_sample_length_position: Option<usize>,
+    channels: u16,
+    /// Only present for IMA_ADPCM.
+    adpcm: Option<ImaAdpcmWriter>,
+    /// Raw samples of [`LgW64Writer::write_samples`], kept to avoid allocating every call.
+    bytes: Vec<u8>,
+    finished: bool,
+}
+impl<W: io::Write + io::Seek> Drop for LgW64Writer<W> {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+impl<W: io::Write + io::Seek> LgW64Writer<W> {
+    pub fn new(mut writer: W, info: &AudioInfo) -> Result<Self> {
+        // Before anything is written, Drop would finish a file that can't have headers.
+        fmt::check_info(info)?;
+        let start = writer.stream_position()? as usize;
+
+        let mut result = Self {
+            writer: Output(Some(writer)),
+            start,
+            data_bytes_written: 0,
+            samples_written: 0,
+            data_size_position: 0,
+            fact_sample_length_position: None,
+            channels: info.channels,
+            adpcm: None,
+            bytes: Vec::new(),
+            finished: false,
+        };
+
+        result.write_header()?;
+        result.write_fmt_chunk(info)?;
+        result.start_data()?;
+
+        Ok(result)
+    }
+
+    #[inline(always)]
+    pub fn write_sample<S: Sample>(&mut self, sample: S, sample_type: SampleType, bits_per_sample: u16) -> Result<()> {
+        match &mut self.adpcm {
+            Some(adpcm) => self.data_bytes_written += adpcm.push(sample, &mut self.writer)?,
+            None => {
+                sample.write(&mut self.writer, sample_type, bits_per_sample)?;
+                self.data_bytes_written += bits_per_sample as u64 / 8;
+            },
+        }
+        self.samples_written += 1;
+
+        Ok(())
+    }
+
+    /// Converts whole blocks of samples and writes each one at once.
+    pub fn write_samples<S: Sample + Copy>(&mut self, samples: &[S], sample_type: SampleType, bits_per_sample: u16) -> Result<()> {
+        // ADPCM is already encoded a block at a time.
+        if self.adpcm.is_some() {
+            for &sample in samples {
+                self.write_sample(sample, sample_type, bits_per_sample)?;
+            }
+
+            return Ok(());
+        }
+
+        self.data_bytes_written += write_sample_parts(&mut self.writer, &mut self.bytes, samples, sample_type, bits_per_sample, ByteOrder::LITTLE_ENDIAN)?;
+        self.samples_written += samples.len() as u64;
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        let current_pos = self.writer.stream_position()?;
+        self.update_headers(current_pos)?;
+        self.writer.flush()?;
+        self.writer.go_to(current_pos as usize)?;
+
+        Ok(())
+    }
+
+    /// Only the first call does anything, even if it fails, so a file is never finished twice.
+    pub fn finish(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+
+        // The last ADPCM block is only written once we know no more samples are coming.
+        if let Some(adpcm) = &mut self.adpcm {
+            self.data_bytes_written += adpcm.finish(&mut self.writer)?;
+        }
+
+        // Alignment of the data chunk.
+        let alignment = aligned(self.data_bytes_written) - self.data_bytes_written;
+        self.writer.write_all(&[0; 8][..alignment as usize])?;
+
+        let end = self.writer.stream_position()?;
+        self.update_headers(end)?;
+        self.writer.go_to(end as usize)?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Finishes the file and returns the writer, positioned at the end of it.
+    pub fn into_inner(mut self) -> Result<W> {
+        self.finish()?;
+
+        Ok(self.writer.take()?)
+    }
+}
+impl<W: io::Write + io::Seek> LgW64Writer<W> {
+    fn write_header(&mut self) -> Result<()> {
+        self.writer.write_all(&RIFF_GUID)?;
+
+        // Empty for now. (size) - position 16.
+        self.writer.write_le_u64(0)?;
+        self.writer.write_all(&WAVE_GUID)?;
+
+        Ok(())
+    }
+
+    fn write_fmt_chunk(&mut self, info: &AudioInfo) -> Result<()> {
+        let data = fmt::fmt_chunk_data(info, ByteOrder::LITTLE_ENDIAN)?;
+
+        self.writer.write_all(&FMT_GUID)?;
+        self.writer.write_le_u64(CHUNK_HEADER_SIZE + data.len() as u64)?;
+        self.writer.write_all(&data)?;
+
+        // Alignment.
+        let alignment = aligned(data.len() as u64) - data.len() as u64;
+        self.writer.write_all(&[0; 8][..alignment as usize])?;
+
+        self.adpcm = ImaAdpcmWriter::for_info(info)?;
+
+        if fmt::needs_fact_chunk(info) {
+            self.write_fact_chunk()?;
+        }
+
+        Ok(())
+    }
+
+    fn write_fact_chunk(&mut self) -> Result<()> {
+        self.writer.write_all(&FACT_GUID)?;
+        self.writer.write_le_u64(CHUNK_HEADER_SIZE + 8)?;
+        self.fact_sample_length_position = Some(self.writer.stream_position()? as usize);
+
+        // Sample frames, empty for now.
+        self.writer.write_le_u64(0)
+    }
+
+    fn start_data(&mut self) -> Result<()> {
+        self.writer.write_all(&DATA_GUID)?;
+        self.data_size_position = self.writer.stream_position()? as usize;
+
+        // Empty for now.
+        self.writer.write_le_u64(0)
+    }
+
+    /// `end` is where the file ends.
+    fn update_headers(&mut self, end: u64) -> Result<()> {
+        // The riff size counts the whole file.
+        self.writer.go_to(self.start + RIFF_SIZE_POSITION)?;
+        self.writer.write_le_u64(end - self.start as u64)?;
+
+        // fact sample frames.
+        if let Some(position) = self.fact_sample_length_position {
+            self.writer.go_to(position)?;
+            self.writer.write_le_u64(self.samples_written / self.channels as u64)?;
+        }
+
+        // Data size, without the alignment.
+        self.writer.go_to(self.data_size_position)?;
+        self.writer.write_le_u64(CHUNK_HEADER_SIZE + self.data_bytes_written)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,686 @@
+use std::io;
+use crate::{error::Error, reader::LgReader, AudioInfo, Result, Sample, SampleType};
+use super::WavFmt;
+
+// ------------------------- IMA ADPCM --------------------------
+const IMA_INDEX_TABLE: [i32; 16] = [
+    -1, -1, -1, -1, 2, 4, 6, 8,
+    -1, -1, -1, -1, 2, 4, 6, 8,
+];
+
+const IMA_STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17,
+    19, 21, 23, 25, 28, 31, 34, 37, 41, 45,
+    50, 55, 60, 66, 73, 80, 88, 97, 107, 118,
+    130, 143, 157, 173, 190, 209, 230, 253, 279, 307,
+    337, 371, 408, 449, 494, 544, 598, 658, 724, 796,
+    876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066,
+    2272, 2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358,
+    5894, 6484, 7132, 7845, 8630, 9493, 10442, 11487, 12635, 13899,
+    15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+
+/// Size of the per channel header at the start of every IMA ADPCM block.
+const IMA_CHANNEL_HEADER_SIZE: usize = 4;
+
+/// Every channel stores 8 samples in 4 bytes before the next channel starts.
+const IMA_CHANNEL_GROUP_SIZE: usize = 4;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct ImaChannel {
+    predictor: i32,
+    step_index: i32,
+}
+impl ImaChannel {
+    fn decode(&mut self, nibble: u8) -> i16 {
+        let step = IMA_STEP_TABLE[self.step_index as usize];
+
+        let mut diff = step >> 3;
+        if nibble & 1 != 0 { diff += step >> 2; }
+        if nibble & 2 != 0 { diff += step >> 1; }
+        if nibble & 4 != 0 { diff += step; }
+        if nibble & 8 != 0 { diff = -diff; }
+
+        self.predictor = (self.predictor + diff).clamp(i16::MIN as i32, i16::MAX as i32);
+        self.step_index = (self.step_index + IMA_INDEX_TABLE[nibble as usize]).clamp(0, 88);
+
+        self.predictor as i16
+    }
+
+    fn encode(&mut self, sample: i16) -> u8 {
+        let mut step = IMA_STEP_TABLE[self.step_index as usize];
+        let mut diff = sample as i32 - self.predictor;
+
+        let mut nibble = if diff < 0 {
+            diff = -diff;
+            8
+        }
+        else {
+            0
+        };
+
+        let mut mask = 4;
+        while mask > 0 {
+            if diff >= step {
+                nibble |= mask;
+                diff -= step;
+            }
+            step >>= 1;
+            mask >>= 1;
+        }
+
+        // Keeps the predictor in sync with what the decoder will see.
+        self.decode(nibble);
+
+        nibble
+    }
+}
+
+/// Decodes one IMA ADPCM block into interleaved 16 bit samples.
+fn decode_ima_block(block: &[u8], channels: usize, samples_per_block: usize, output: &mut Vec<i16>) -> Result<()> {
+    let header_size = IMA_CHANNEL_HEADER_SIZE * channels;
+    if block.len() < header_size {
+        return Err(Error::WrongFmtInfo("IMA_ADPCM block is smaller than its header!".to_string()));
+    }
+
+    let mut states = vec![ImaChannel::default(); channels];
+    for (ch, state) in states.iter_mut().enumerate() {
+        let header = &block[ch * IMA_CHANNEL_HEADER_SIZE..];
+
+        state.predictor = i16::from_le_bytes([header[0], header[1]]) as i32;
+        state.step_index = (header[2] as i32).clamp(0, 88);
+    }
+
+    let groups = (block.len() - header_size) / (IMA_CHANNEL_GROUP_SIZE * channels);
+    let frames = (1 + groups * 8).min(samples_per_block);
+
+    let start = output.len();
+    output.resize(start + frames * channels, 0);
+    let output = &mut output[start..];
+
+    // The first frame is stored in the header.
+    for (ch, state) in states.iter().enumerate() {
+        output[ch] = state.predictor as i16;
+    }
+
+    for group in 0..groups {
+        for (ch, state) in states.iter_mut().enumerate() {
+            let offset = header_size + (group * channels + ch) * IMA_CHANNEL_GROUP_SIZE;
+            let bytes = &block[offset..offset + IMA_CHANNEL_GROUP_SIZE];
+
+            for (i, byte) in bytes.iter().enumerate() {
+                // Low nibble first.
+                for (j, nibble) in [byte & 0x0F, byte >> 4].into_iter().enumerate() {
+                    let frame = 1 + group * 8 + i * 2 + j;
+                    let value = state.decode(nibble);
+
+                    if frame < frames {
+                        output[frame * channels + ch] = value;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Default block_align for IMA ADPCM, grows with the sample rate like most encoders do.
+pub(crate) fn ima_block_align(channels: u16, sample_rate: u32) -> Result<u16> {
+    let multiplier = (sample_rate / 11025).clamp(1, 4) as usize;
+    let block_align = 256 * channels as usize * multiplier;
+
+    match u16::try_from(block_align) {
+        Ok(block_align) if channels > 0 => Ok(block_align),
+        _ => Err(Error::WrongFmtInfo(std::format!(
+            "IMA_ADPCM does not support {} channels at {} Hz!", 
+            channels, 
+            sample_rate
+        ))),
+    }
+}
+
+/// How many sample frames fit in a block of `block_align` bytes.
+pub(super) fn ima_samples_per_block(channels: u16, block_align: u16) -> Result<u16> {
+    let channels = channels as usize;
+    let header_size = IMA_CHANNEL_HEADER_SIZE * channels;
+
+    if channels == 0 || block_align as usize <= header_size {
+        return Err(Error::WrongFmtInfo("IMA_ADPCM block_align is too small for its channels!".to_string()));
+    }
+
+    u16::try_from((block_align as usize - header_size) * 2 / channels + 1)
+        .map_err(|_| Error::WrongFmtInfo("IMA_ADPCM block_align is too big!".to_string()))
+}
+
+/// Encodes interleaved samples into IMA ADPCM blocks.
+pub(crate) struct ImaAdpcmEncoder {
+    channels: usize,
+    samples_per_block: usize,
+    states: Vec<ImaChannel>,
+    /// Interleaved samples of the block being built.
+    pending: Vec<i16>,
+}
+impl ImaAdpcmEncoder {
+    pub(crate) fn new(channels: u16, block_align: u16) -> Result<Self> {
+        Ok(Self {
+            channels: channels as usize,
+            samples_per_block: ima_samples_per_block(channels, block_align)? as usize,
+            states: vec![ImaChannel::default(); channels as usize],
+            pending: Vec::new(),
+        })
+    }
+
+    /// Stores the sample, returns true when `block` was filled with a complete block.
+    pub(crate) fn push(&mut self, sample: i16, block: &mut Vec<u8>) -> bool {
+        self.pending.push(sample);
+
+        if self.pending.len() < self.samples_per_block * self.channels {
+            return false;
+        }
+
+        self.encode_block(block);
+        true
+    }
+
+    /// Encodes the pending samples padded with silence, returns false if there was nothing pending.
+    pub(crate) fn finish(&mut self, block: &mut Vec<u8>) -> bool {
+        if self.pending.is_empty() {
+            return false;
+        }
+
+        self.pending.resize(self.samples_per_block * self.channels, 0);
+        self.encode_block(block);
+        true
+    }
+
+    fn encode_block(&mut self, block: &mut Vec<u8>) {
+        block.clear();
+
+        // The first frame goes straight into the header.
+        for (ch, state) in self.states.iter_mut().enumerate() {
+            let sample = self.pending[ch];
+            state.predictor = sample as i32;
+
+            block.extend_from_slice(&sample.to_le_bytes());
+            block.push(state.step_index as u8);
+            // Reserved.
+            block.push(0);
+        }
+
+        let groups = (self.samples_per_block - 1) / 8;
+        for group in 0..groups {
+            for (ch, state) in self.states.iter_mut().enumerate() {
+                for i in 0..IMA_CHANNEL_GROUP_SIZE {
+                    let frame = 1 + group * 8 + i * 2;
+                    let low = state.encode(self.pending[frame * self.channels + ch]);
+                    let high = state.encode(self.pending[(frame + 1) * self.channels + ch]);
+
+                    block.push(low | (high << 4));
+                }
+            }
+        }
+
+        self.pending.clear();
+    }
+}
+
+/// The IMA ADPCM side of the writers, it writes every block as soon as it is complete.
+pub(crate) struct ImaAdpcmWriter {
+    encoder: ImaAdpcmEncoder,
+    block: Vec<u8>,
+}
+impl ImaAdpcmWriter {
+    /// `None` if `info` is not IMA_ADPCM.
+    pub(crate) fn for_info(info: &AudioInfo) -> Result<Option<Self>> {
+        if info.sample_type != Some(SampleType::IMA_ADPCM) {
+            return Ok(None);
+        }
+
+        let block_align = ima_block_align(info.channels, info.sample_rate)?;
+        Ok(Some(Self {
+            encoder: ImaAdpcmEncoder::new(info.channels, block_align)?,
+            block: Vec::new(),
+        }))
+    }
+
+    /// Returns the bytes written to `writer`, 0 until a block is complete.
+    pub(crate) fn push<S: Sample>(&mut self, sample: S, writer: &mut impl io::Write) -> Result<u64> {
+        if !self.encoder.push(sample_to_i16(sample)?, &mut self.block) {
+            return Ok(0);
+        }
+        writer.write_all(&self.block)?;
+
+        Ok(self.block.len() as u64)
+    }
+
+    /// Writes the last block, padded with silence. Returns the bytes written to `writer`.
+    pub(crate) fn finish(&mut self, writer: &mut impl io::Write) -> Result<u64> {
+        if !self.encoder.finish(&mut self.block) {
+            return Ok(0);
+        }
+        writer.write_all(&self.block)?;
+
+        Ok(self.block.len() as u64)
+    }
+}
+
+// ------------------------- MS ADPCM --------------------------
+const MS_ADAPTATION_TABLE: [i32; 16] = [
+    230, 230, 230, 230, 307, 409, 512, 614,
+    768, 614, 512, 409, 307, 230, 230, 230,
+];
+
+/// Size of the per channel header at the start of every MS ADPCM block.
+const MS_CHANNEL_HEADER_SIZE: usize = 7;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct MsChannel {
+    coefficient_1: i32,
+    coefficient_2: i32,
+    delta: i32,
+    sample_1: i32,
+    sample_2: i32,
+}
+impl MsChannel {
+    fn decode(&mut self, nibble: u8) -> i16 {
+        // Sign extend the nibble.
+        let signed = if nibble & 8 != 0 { nibble as i32 - 16 } else { nibble as i32 };
+
+        let predictor = (self.sample_1 * self.coefficient_1 + self.sample_2 * self.coefficient_2) >> 8;
+        let sample = (predictor + signed * self.delta).clamp(i16::MIN as i32, i16::MAX as i32);
+
+        self.sample_2 = self.sample_1;
+        self.sample_1 = sample;
+        // Broken files can make delta grow forever, so it is kept where it can not overflow.
+        self.delta = ((MS_ADAPTATION_TABLE[nibble as usize] * self.delta) >> 8).clamp(16, i32::MAX / 768);
+
+        sample as i16
+    }
+}
+
+/// Decodes one MS ADPCM block into interleaved 16 bit samples.
+fn decode_ms_block(
+    block: &[u8], 
+    channels: usize, 
+    samples_per_block: usize, 
+    coefficients: &[(i16, i16)], 
+    output: &mut Vec<i16>
+) -> Result<()> {
+    let header_size = MS_CHANNEL_HEADER_SIZE * channels;
+    if block.len() < header_size {
+        return Err(Error::WrongFmtInfo("MS_ADPCM block is smaller than its header!".to_string()));
+    }
+
+    // The header is stored field by field, each field for every channel.
+    let read_i16 = |index: usize| i16::from_le_bytes([block[index], block[index + 1]]) as i32;
+
+    let mut states = vec![MsChannel::default(); channels];
+    for (ch, state) in states.iter_mut().enumerate() {
+        let Some(&(coefficient_1, coefficient_2)) = coefficients.get(block[ch] as usize) else {
+            return Err(Error::WrongFmtInfo("MS_ADPCM block uses a coefficient that is not in the fmt chunk!".to_string()));
+        };
+
+        state.coefficient_1 = coefficient_1 as i32;
+        state.coefficient_2 = coefficient_2 as i32;
+        state.delta = read_i16(channels + ch * 2);
+        state.sample_1 = read_i16(channels * 3 + ch * 2);
+        state.sample_2 = read_i16(channels * 5 + ch * 2);
+    }
+
+    // Two nibbles per byte, high nibble first, interleaved between the channels.
+    let nibbles = (block.len() - header_size) * 2;
+    let frames = (2 + nibbles / channels).min(samples_per_block);
+
+    let start = output.len();
+    output.reserve(frames * channels);
+
+    // The first two frames are stored in the header, oldest first.
+    output.extend(states.iter().map(|state| state.sample_2 as i16));
+    output.extend(states.iter().map(|state| state.sample_1 as i16));
+
+    let data = block[header_size..].iter().flat_map(|byte| [byte >> 4, byte & 0x0F]);
+    for (i, nibble) in data.enumerate() {
+        if output.len() - start >= frames * channels {
+            break;
+        }
+
+        output.push(states[i % channels].decode(nibble));
+    }
+
+    Ok(())
+}
+
+// ------------------------- BLOCKS --------------------------
+
+enum AdpcmCodec {
+    Ima,
+    Ms(Vec<(i16, i16)>),
+}
+impl AdpcmCodec {
+    fn channel_header_size(&self) -> usize {
+        match self {
+            Self::Ima => IMA_CHANNEL_HEADER_SIZE,
+            Self::Ms(_) => MS_CHANNEL_HEADER_SIZE,
+        }
+    }
+    
+    /// Number of sample frames in a block that was cut short, like the last one in a file.
+    fn frames_in(&self, block_len: usize, channels: usize) -> usize {
+        let header_size = self.channel_header_size() * channels;
+        if block_len < header_size {
+            return 0;
+        }
+
+        match self {
+            Self::Ima => 1 + (block_len - header_size) / (IMA_CHANNEL_GROUP_SIZE * channels) * 8,
+            Self::Ms(_) => 2 + (block_len - header_size) * 2 / channels,
+        }
+    }
+}
+
+/// Reads ADPCM blocks from the data chunk and hands out the decoded samples one by one.
+pub(crate) struct AdpcmBlocks {
+    codec: AdpcmCodec,
+    channels: usize,
+    block_align: usize,
+    samples_per_block: usize,
+    /// Bytes of the data chunk that were not read yet.
+    data_remaining: usize,
+    /// Samples that were not handed out yet, blocks may be padded past the end of the audio.
+    samples_remaining: usize,
+    data_len: usize,
+    sample_len: usize,
+    block: Vec<u8>,
+    decoded: Vec<i16>,
+    position: usize,
+    /// Samples of the next block that are skipped, set when seeking into the middle of a block.
+    skip: usize,
+}
+impl AdpcmBlocks {
+    pub(crate) fn new(fmt: &WavFmt, data_len: usize, sample_len: usize) -> Self {
+        Self {
+            codec: AdpcmBlocks::codec(fmt),
+            channels: fmt.info.channels as usize,
+            block_align: fmt.block_align as usize,
+            samples_per_block: fmt.samples_per_block as usize,
+            data_remaining: data_len,
+            samples_remaining: sample_len,
+            data_len,
+            sample_len,
+            block: Vec::with_capacity(fmt.block_align as usize),
+            decoded: Vec::new(),
+            position: 0,
+            skip: 0,
+        }
+    }
+
+    /// Starts again from the sample frame `frame`, returns the offset in the data chunk of the block it is in.
+    pub(crate) fn seek(&mut self, frame: usize) -> usize {
+        let block = frame / self.samples_per_block;
+        let offset = block.saturating_mul(self.block_align).min(self.data_len);
+
+        self.data_remaining = self.data_len - offset;
+        self.samples_remaining = self.sample_len.saturating_sub(frame.saturating_mul(self.channels));
+        self.decoded.clear();
+        self.position = 0;
+        self.skip = (frame % self.samples_per_block) * self.channels;
+
+        offset
+    }
+
+    /// Number of interleaved samples stored in `data_len` bytes of blocks.
+    pub(crate) fn sample_len(fmt: &WavFmt, data_len: usize) -> usize {
+        let channels = fmt.info.channels as usize;
+        let block_align = fmt.block_align as usize;
+
+        let full_blocks = data_len / block_align;
+        let last_frames = AdpcmBlocks::codec(fmt)
+            .frames_in(data_len % block_align, channels)
+            .min(fmt.samples_per_block as usize);
+
+        (full_blocks * fmt.samples_per_block as usize + last_frames) * channels
+    }
+
+    fn codec(fmt: &WavFmt) -> AdpcmCodec {
+        match fmt.info.sample_type {
+            Some(SampleType::MS_ADPCM) => AdpcmCodec::Ms(fmt.coefficients.clone()),
+            _ => AdpcmCodec::Ima,
+        }
+    }
+
+    pub(crate) fn next_sample<S: Sample>(&mut self, reader: &mut impl LgReader) -> Result<Option<S>> {
+        if self.samples_remaining == 0 {
+            return Ok(None);
+        }
+
+        if self.position >= self.decoded.len() && !self.read_block(reader)? {
+            return Ok(None);
+        }
+
+        let value = self.decoded[self.position];
+        self.position += 1;
+        self.samples_remaining -= 1;
+
+        i16_to_sample(value).map(Some)
+    }
+
+    /// Appends up to `max` decoded samples to `bytes` as 16 bit little endian, returns how many.
+    pub(crate) fn read_samples(&mut self, reader: &mut impl LgReader, max: usize, bytes: &mut Vec<u8>) -> Result<usize> {
+        let mut len = 0;
+
+        while len < max && self.samples_remaining > 0 {
+            if self.position >= self.decoded.len() && !self.read_block(reader)? {
+                break;
+            }
+
+            let n = (max - len).min(self.decoded.len() - self.position).min(self.samples_remaining);
+            for value in &self.decoded[self.position..self.position + n] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+
+            self.position += n;
+            self.samples_remaining -= n;
+            len += n;
+        }
+
+        Ok(len)
+    }
+
+    fn read_block(&mut self, reader: &mut impl LgReader) -> Result<bool> {
+        let len = self.block_align.min(self.data_remaining);
+        if len < self.codec.channel_header_size() * self.channels {
+            return Ok(false);
+        }
+
+        self.block.resize(len, 0);
+        reader.read_into(&mut self.block)?;
+        self.data_remaining -= len;
+
+        self.decoded.clear();
+        self.position = std::mem::take(&mut self.skip);
+        match &self.codec {
+            AdpcmCodec::Ima => decode_ima_block(&self.block, self.channels, self.samples_per_block, &mut self.decoded)?,
+            AdpcmCodec::Ms(coefficients) => decode_ms_block(
+                &self.block, 
+                self.channels, 
+                self.samples_per_block, 
+                coefficients, 
+                &mut self.decoded
+            )?,
+        }
+
+        Ok(self.position < self.decoded.len())
+    }
+}
+
+// ------------------------- SAMPLE --------------------------
+
+/// Hands a decoded value to the [`Sample`] trait as 16 bit PCM.
+fn i16_to_sample<S: Sample>(value: i16) -> Result<S> {
+    S::read(&mut &value.to_le_bytes()[..], SampleType::INT, 16)
+}
+
+/// Gets a 16 bit value out of any [`Sample`], as if it was written as 16 bit PCM.
+fn sample_to_i16<S: Sample>(sample: S) -> Result<i16> {
+    let mut buf = [0u8; 2];
+    sample.write(&mut io::Cursor::new(&mut buf[..]), SampleType::INT, 16)?;
+
+    Ok(i16::from_le_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use crate::{decoder::LgDecoder, wav::{fixtures::{encode, info}, LgWavDecoder, WavContainer, WavFmt, WAVE_FORMAT_ADPCM}, ByteOrder, SampleType};
+    use super::{decode_ima_block, decode_ms_block, ima_block_align, ima_samples_per_block, ImaAdpcmEncoder, ImaAdpcmWriter};
+
+    /// The coefficients every MS ADPCM file starts with.
+    const MS_COEFFICIENTS: [(i16, i16); 7] = [(256, 0), (512, -256), (0, 0), (192, 64), (240, 0), (460, -208), (392, -232)];
+
+    /// Two channels of sine waves.
+    fn sine(frames: usize) -> Vec<i16> {
+        (0..frames)
+            .flat_map(|i| {
+                let t = i as f64 / 8000.0;
+                [
+                    ((t * 440.0 * std::f64::consts::TAU).sin() * 20000.0) as i16,
+                    ((t * 220.0 * std::f64::consts::TAU).sin() * 10000.0) as i16,
+                ]
+            })
+            .collect()
+    }
+
+    /// Encodes `samples` into blocks and decodes them again, the padding of the last block included.
+    fn ima_round_trip(samples: &[i16], channels: u16, block_align: u16) -> Vec<i16> {
+        let samples_per_block = ima_samples_per_block(channels, block_align).unwrap() as usize;
+        let mut encoder = ImaAdpcmEncoder::new(channels, block_align).unwrap();
+        let mut block = Vec::new();
+        let mut decoded = Vec::new();
+
+        for &sample in samples {
+            if encoder.push(sample, &mut block) {
+                assert_eq!(block.len(), block_align as usize);
+                decode_ima_block(&block, channels as usize, samples_per_block, &mut decoded).unwrap();
+            }
+        }
+        if encoder.finish(&mut block) {
+            decode_ima_block(&block, channels as usize, samples_per_block, &mut decoded).unwrap();
+        }
+
+        decoded
+    }
+
+    #[test]
+    fn ima_round_trip_stays_close() {
+        let samples = sine(3000);
+        let block_align = ima_block_align(2, 8000).unwrap();
+        let decoded = ima_round_trip(&samples, 2, block_align);
+
+        // 505 frames per block, the last one is padded.
+        assert_eq!(decoded.len(), 3030 * 2);
+        // The first frame of every block is stored as is.
+        assert_eq!(decoded[..2], samples[..2]);
+        assert_eq!(decoded[505 * 2..505 * 2 + 2], samples[505 * 2..505 * 2 + 2]);
+
+        // The step size starts at its smallest, so the first frames are left out while it adapts.
+        let max_error = samples.iter().zip(&decoded).skip(64).map(|(a, b)| (*a as i32 - *b as i32).abs()).max().unwrap();
+        assert!(max_error < 1500, "max error {max_error}");
+    }
+
+    #[test]
+    fn ima_seek() {
+        let file = encode(info(SampleType::IMA_ADPCM, 4), WavContainer::RIFF, &sine(3000));
+        let mut decoder = LgWavDecoder::from_seekable(io::Cursor::new(file)).unwrap();
+        let decoded: Vec<i16> = decoder.samples().collect();
+
+        // In the middle of the third block.
+        decoder.seek_frame(1234).unwrap();
+        let mut buffer = [0i16; 20];
+        assert_eq!(decoder.read_samples(&mut buffer).unwrap(), 20);
+        assert_eq!(buffer[..], decoded[1234 * 2..1234 * 2 + 20]);
+
+        decoder.rewind().unwrap();
+        assert_eq!(decoder.samples::<i16>().collect::<Vec<_>>(), decoded);
+
+        decoder.seek_frame(3000).unwrap();
+        assert_eq!(decoder.samples::<i16>().count(), 0);
+    }
+
+    #[test]
+    fn ima_block_limits() {
+        assert!(ima_block_align(0, 44100).is_err());
+        assert!(ima_block_align(64, 44100).is_err());
+        assert!(ima_block_align(63, 44100).is_ok());
+        assert_eq!(ima_block_align(2, 8000).unwrap(), 512);
+        assert_eq!(ima_block_align(2, 44100).unwrap(), 2048);
+
+        assert_eq!(ima_samples_per_block(2, 512).unwrap(), 505);
+        assert!(ima_samples_per_block(2, 8).is_err());
+        assert!(ima_samples_per_block(0, 512).is_err());
+        assert!(ImaAdpcmEncoder::new(2, 8).is_err());
+    }
+
+    #[test]
+    fn ima_writer_writes_whole_blocks() {
+        assert!(ImaAdpcmWriter::for_info(&info(SampleType::INT, 16)).unwrap().is_none());
+
+        // Stereo at 8000 Hz has blocks of 512 bytes and 505 frames.
+        let mut adpcm = ImaAdpcmWriter::for_info(&info(SampleType::IMA_ADPCM, 4)).unwrap().unwrap();
+        let mut file = Vec::new();
+        let written: u64 = sine(506).into_iter().map(|sample| adpcm.push(sample, &mut file).unwrap()).sum();
+        assert_eq!(written, 512);
+        assert_eq!(file.len(), 512);
+
+        // The last frame is padded into a whole block, and nothing is left after it.
+        assert_eq!(adpcm.finish(&mut file).unwrap(), 512);
+        assert_eq!(adpcm.finish(&mut file).unwrap(), 0);
+        assert_eq!(file.len(), 1024);
+    }
+
+    #[test]
+    fn ms_block_matches_the_reference() {
+        // Coefficient index 1, delta 16, sample_1 100 and sample_2 50, then four nibbles of 1.
+        let block = [1, 16, 0, 100, 0, 50, 0, 0x11, 0x11];
+        let mut decoded = Vec::new();
+        decode_ms_block(&block, 1, 6, &MS_COEFFICIENTS, &mut decoded).unwrap();
+
+        assert_eq!(decoded, [50, 100, 166, 248, 346, 460]);
+
+        // Only the coefficients of the fmt chunk can be used.
+        assert!(decode_ms_block(&[7, 16, 0, 100, 0, 50, 0, 0x11], 1, 4, &MS_COEFFICIENTS, &mut decoded).is_err());
+    }
+
+    /// The data of a mono MS ADPCM fmt chunk.
+    fn ms_fmt(block_align: u16, samples_per_block: u16) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&WAVE_FORMAT_ADPCM.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&8000u32.to_le_bytes());
+        data.extend_from_slice(&4096u32.to_le_bytes());
+        data.extend_from_slice(&block_align.to_le_bytes());
+        data.extend_from_slice(&4u16.to_le_bytes());
+        data.extend_from_slice(&(4 + MS_COEFFICIENTS.len() as u16 * 4).to_le_bytes());
+        data.extend_from_slice(&samples_per_block.to_le_bytes());
+        data.extend_from_slice(&(MS_COEFFICIENTS.len() as u16).to_le_bytes());
+        for (coefficient_1, coefficient_2) in MS_COEFFICIENTS {
+            data.extend_from_slice(&coefficient_1.to_le_bytes());
+            data.extend_from_slice(&coefficient_2.to_le_bytes());
+        }
+
+        data
+    }
+
+    #[test]
+    fn ms_fmt_limits() {
+        let fmt = WavFmt::read(&ms_fmt(256, 500), ByteOrder::LITTLE_ENDIAN).unwrap();
+        assert_eq!(fmt.samples_per_block, 500);
+        assert_eq!(fmt.coefficients, MS_COEFFICIENTS);
+
+        // Not even the header fits.
+        assert!(WavFmt::read(&ms_fmt(0, 500), ByteOrder::LITTLE_ENDIAN).is_err());
+        // 249 bytes of nibbles after the header only hold 500 frames.
+        assert!(WavFmt::read(&ms_fmt(256, 501), ByteOrder::LITTLE_ENDIAN).is_err());
+        assert!(WavFmt::read(&ms_fmt(256, 1), ByteOrder::LITTLE_ENDIAN).is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{reader::LgReader, Result};
+use super::metadata::read_zstr;
+
+// Sizes of the fixed fields, from EBU Tech 3285.
+const DESCRIPTION_SIZE: usize =             256;
+const ORIGINATOR_SIZE: usize =              32;
+const ORIGINATOR_REFERENCE_SIZE: usize =    32;
+const ORIGINATION_DATE_SIZE: usize =        10;
+const ORIGINATION_TIME_SIZE: usize =        8;
+const UMID_SIZE: usize =                    64;
+const RESERVED_SIZE: usize =                180;
+
+/// Size of everything before the coding history.
+const BEXT_FIXED_SIZE: usize = DESCRIPTION_SIZE
+    + ORIGINATOR_SIZE
+    + ORIGINATOR_REFERENCE_SIZE
+    + ORIGINATION_DATE_SIZE
+    + ORIGINATION_TIME_SIZE
+    + 8     // time_reference
+    + 2     // version
+    + UMID_SIZE
+    + 10    // loudness
+    + RESERVED_SIZE;
+
+/// Broadcast Wave Format extension (bext chunk), as described by EBU Tech 3285.
+/// Text that does not fit its field is cut short when writing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WavBext {
+    pub description: String,
+    pub originator: String,
+    pub originator_reference: String,
+    /// yyyy-mm-dd
+    pub origination_date: String,
+    /// hh:mm:ss
+    pub origination_time: String,
+    /// Sample frames since midnight until the first sample.
+    pub time_reference: u64,
+    pub version: u16,
+    /// SMPTE 330M UMID, all zeroes when not used.
+    pub umid: [u8; UMID_SIZE],
+    /// Integrated loudness in LUFS * 100, version 2 and up.
+    pub loudness_value: i16,
+    /// Loudness range in LU * 100, version 2 and up.
+    pub loudness_range: i16,
+    /// Maximum true peak in dBTP * 100, version 2 and up.
+    pub max_true_peak_level: i16,
+    /// Highest momentary loudness in LUFS * 100, version 2 and up.
+    pub max_momentary_loudness: i16,
+    /// Highest short term loudness in LUFS * 100, version 2 and up.
+    pub max_short_term_loudness: i16,
+    pub coding_history: String,
+}
+impl Default for WavBext {
+    fn default() -> Self {
+        Self {
+            description: String::new(),
+            originator: String::new(),
+            originator_reference: String::new(),
+            origination_date: String::new(),
+            origination_time: String::new(),
+            time_reference: 0,
+            version: 2,
+            umid: [0; UMID_SIZE],
+            loudness_value: 0,
+            loudness_range: 0,
+            max_true_peak_level: 0,
+            max_momentary_loudness: 0,
+            max_short_term_loudness: 0,
+            coding_history: String::new(),
+        }
+    }
+}
+impl WavBext {
+    /// Reads the bext chunk data. Old versions have a smaller reserved area, what is missing is read as zeroes.
+    pub(super) fn read(data: &[u8]) -> Result<Self> {
+        let mut fixed = [0; BEXT_FIXED_SIZE];
+        let fixed_size = data.len().min(BEXT_FIXED_SIZE);
+        fixed[..fixed_size].copy_from_slice(&data[..fixed_size]);
+
+        let mut reader = &fixed[..];
+        let description = read_zstr(&reader.read_next_bytes::<DESCRIPTION_SIZE>()?);
+        let originator = read_zstr(&reader.read_next_bytes::<ORIGINATOR_SIZE>()?);
+        let originator_reference = read_zstr(&reader.read_next_bytes::<ORIGINATOR_REFERENCE_SIZE>()?);
+        let origination_date = read_zstr(&reader.read_next_bytes::<ORIGINATION_DATE_SIZE>()?);
+        let origination_time = read_zstr(&reader.read_next_bytes::<ORIGINATION_TIME_SIZE>()?);
+        let time_reference = reader.read_le_u64()?;
+
+        Ok(Self {
+            description,
+            originator,
+            originator_reference,
+            origination_date,
+            origination_time,
+            time_reference,
+            version: reader.read_le_u16()?,
+            umid: reader.read_next_bytes()?,
+            loudness_value: reader.read_le_i16()?,
+            loudness_range: reader.read_le_i16()?,
+            max_true_peak_level: reader.read_le_i16()?,
+            max_momentary_loudness: reader.read_le_i16()?,
+            max_short_term_loudness: reader.read_le_i16()?,
+            coding_history: read_zstr(&data[fixed_size..]),
+        })
+    }
+
+    /// The bext chunk data.
+    pub(super) fn to_chunk_data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(BEXT_FIXED_SIZE + self.coding_history.len());
+
+        write_fixed_str(&mut data, &self.description, DESCRIPTION_SIZE);
+        write_fixed_str(&mut data, &self.originator, ORIGINATOR_SIZE);
+        write_fixed_str(&mut data, &self.originator_reference, ORIGINATOR_REFERENCE_SIZE);
+        write_fixed_str(&mut data, &self.origination_date, ORIGINATION_DATE_SIZE);
+        write_fixed_str(&mut data, &self.origination_time, ORIGINATION_TIME_SIZE);
+        data.extend_from_slice(&self.time_reference.to_le_bytes());
+        data.extend_from_slice(&self.version.to_le_bytes());
+        data.extend_from_slice(&self.umid);
+        data.extend_from_slice(&self.loudness_value.to_le_bytes());
+        data.extend_from_slice(&self.loudness_range.to_le_bytes());
+        data.extend_from_slice(&self.max_true_peak_level.to_le_bytes());
+        data.extend_from_slice(&self.max_momentary_loudness.to_le_bytes());
+        data.extend_from_slice(&self.max_short_term_loudness.to_le_bytes());
+        data.resize(BEXT_FIXED_SIZE, 0);
+        data.extend_from_slice(self.coding_history.as_bytes());
+
+        data
+    }
+}
+
+/// Writes the text into a field of `size` bytes, padded with zeroes.
+fn write_fixed_str(data: &mut Vec<u8>, text: &str, size: usize) {
+    let mut end = text.len().min(size);
+    // Never cut a character in half.
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    data.extend_from_slice(&text.as_bytes()[..end]);
+    data.resize(data.len() + size - end, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use crate::{encoder::LgEncoder, SampleType};
+    use crate::wav::fixtures::{info, samples};
+    use crate::wav::{LgWavDecoder, LgWavEncoder, WavContainer};
+    use super::{write_fixed_str, WavBext, BEXT_FIXED_SIZE};
+
+    fn bext() -> WavBext {
+        WavBext {
+            description: "Description".to_string(),
+            originator: "Originator".to_string(),
+            originator_reference: "Reference".to_string(),
+            origination_date: "2024-01-31".to_string(),
+            origination_time: "12:34:56".to_string(),
+            time_reference: 0x0102_0304_0506_0708,
+            version: 2,
+            umid: [7; 64],
+            loudness_value: -2300,
+            loudness_range: 500,
+            max_true_peak_level: -100,
+            max_momentary_loudness: -1800,
+            max_short_term_loudness: -2000,
+            coding_history: "A=PCM,F=48000,W=24,M=stereo,T=original\r\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn fixed_layout() {
+        assert_eq!(BEXT_FIXED_SIZE, 602);
+
+        let bext = bext();
+        let data = bext.to_chunk_data();
+        assert_eq!(data.len(), 602 + bext.coding_history.len());
+
+        assert_eq!(&data[..11], b"Description");
+        assert_eq!(data[11..256], [0; 245]);
+        assert_eq!(&data[256..266], b"Originator");
+        assert_eq!(&data[288..297], b"Reference");
+        assert_eq!(&data[320..330], b"2024-01-31");
+        assert_eq!(&data[330..338], b"12:34:56");
+        assert_eq!(data[338..346], bext.time_reference.to_le_bytes());
+        assert_eq!(data[346..348], 2u16.to_le_bytes());
+        assert_eq!(data[348..412], [7; 64]);
+        assert_eq!(data[412..414], (-2300i16).to_le_bytes());
+        assert_eq!(data[420..422], (-2000i16).to_le_bytes());
+        // Reserved.
+        assert_eq!(data[422..602], [0; 180]);
+        assert_eq!(&data[602..], bext.coding_history.as_bytes());
+    }
+
+    #[test]
+    fn fixed_str_is_cut_at_a_char_boundary() {
+        let mut data = Vec::new();
+        // 'é' takes 2 bytes, the third one does not fit.
+        write_fixed_str(&mut data, "ééé", 5);
+        assert_eq!(data, [0xC3, 0xA9, 0xC3, 0xA9, 0]);
+
+        let mut data = Vec::new();
+        write_fixed_str(&mut data, "abcdef", 4);
+        assert_eq!(data, b"abcd");
+    }
+
+    #[test]
+    fn bext_round_trip() {
+        let bext = bext();
+        assert_eq!(WavBext::read(&bext.to_chunk_data()).unwrap(), bext);
+
+        let mut encoder = LgWavEncoder::from_writer(io::Cursor::new(Vec::new()), info(SampleType::INT, 16), WavContainer::RIFF).unwrap();
+        encoder.write_bext(&bext).unwrap();
+        encoder.encode_samples(&samples(2000)).unwrap();
+        let file = encoder.into_inner().unwrap().into_inner();
+
+        let decoder = LgWavDecoder::from_reader(&file[..]).unwrap();
+        assert_eq!(decoder.bext(), Some(&bext));
+    }
+}
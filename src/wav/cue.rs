@@ -0,0 +1,255 @@
+use crate::{reader::LgReader, Result};
+use super::{metadata::read_zstr, sub_chunks, write_sub_chunk};
+
+/// Size of each cue point in the cue chunk.
+const CUE_POINT_SIZE: usize = 24;
+
+/// A marker in the audio, made from the cue chunk and the LIST-adtl chunk.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct WavCuePoint {
+    /// Unique among the cue points of the file, the adtl texts use it to refer to the cue point.
+    pub id: u32,
+    /// Sample frame the marker is at.
+    pub position: u32,
+    /// From the labl sub-chunk.
+    pub label: Option<String>,
+    /// From the note sub-chunk.
+    pub note: Option<String>,
+    /// Sample frames the marker covers, from the ltxt sub-chunk. 0 for a single point.
+    pub length: u32,
+    /// From the ltxt sub-chunk.
+    pub text: Option<String>,
+}
+impl WavCuePoint {
+    pub fn new(id: u32, position: u32) -> Self {
+        Self {
+            id,
+            position,
+            ..Default::default()
+        }
+    }
+}
+
+/// The texts of a LIST-adtl chunk, they go along with the cue point of the same id.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone)]
+pub(crate) enum WavCueText {
+    LABL(String),
+    NOTE(String),
+    LTXT {
+        length: u32,
+        text: String,
+    },
+}
+
+/// Reads the cue points of a cue chunk, without any text.
+pub(super) fn read_cue(mut data: &[u8]) -> Result<Vec<WavCuePoint>> {
+    let count = data.read_le_u32()? as usize;
+    // Do not trust the count more than the chunk size.
+    let count = count.min(data.len() / CUE_POINT_SIZE);
+
+    let mut cue_points = Vec::with_capacity(count);
+    for _ in 0..count {
+        let id = data.read_le_u32()?;
+        // dwPosition, fccChunk, dwChunkStart and dwBlockStart are not needed without a playlist.
+        data.skip_next_bytes::<16>()?;
+        let position = data.read_le_u32()?;
+
+        cue_points.push(WavCuePoint::new(id, position));
+    }
+
+    Ok(cue_points)
+}
+
+/// Reads the sub-chunks of a LIST-adtl chunk, `data` starts after the "adtl" list type.
+pub(super) fn read_adtl(data: &[u8]) -> Result<Vec<(u32, WavCueText)>> {
+    let mut texts = Vec::new();
+
+    for (id, mut sub_data) in sub_chunks(data)? {
+        if sub_data.len() < 4 {
+            continue;
+        }
+        let cue_id = sub_data.read_le_u32()?;
+
+        let text = match &id {
+            b"labl" => WavCueText::LABL(read_zstr(sub_data)),
+            b"note" => WavCueText::NOTE(read_zstr(sub_data)),
+            b"ltxt" if sub_data.len() >= 16 => {
+                let length = sub_data.read_le_u32()?;
+                // dwPurposeID, wCountry, wLanguage, wDialect and wCodePage.
+                sub_data.skip_next_bytes::<12>()?;
+
+                WavCueText::LTXT {
+                    length,
+                    text: read_zstr(sub_data),
+                }
+            },
+            _ => continue,
+        };
+
+        texts.push((cue_id, text));
+    }
+
+    Ok(texts)
+}
+
+/// Adds the cue points, the ones that already exist only get their position updated.
+pub(super) fn merge_cue_points(cue_points: &mut Vec<WavCuePoint>, other: Vec<WavCuePoint>) {
+    for other in other {
+        cue_point_mut(cue_points, other.id).position = other.position;
+    }
+}
+
+/// Gives the texts to their cue points, the adtl chunk may come before the cue chunk.
+pub(super) fn merge_cue_texts(cue_points: &mut Vec<WavCuePoint>, texts: Vec<(u32, WavCueText)>) {
+    for (id, text) in texts {
+        let cue_point = cue_point_mut(cue_points, id);
+
+        match text {
+            WavCueText::LABL(label) => cue_point.label = Some(label),
+            WavCueText::NOTE(note) => cue_point.note = Some(note),
+            WavCueText::LTXT { length, text } => {
+                cue_point.length = length;
+                cue_point.text = Some(text);
+            },
+        }
+    }
+}
+
+/// The cue chunk data.
+pub(super) fn to_cue_data(cue_points: &[WavCuePoint]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(4 + cue_points.len() * CUE_POINT_SIZE);
+    data.extend_from_slice(&(cue_points.len() as u32).to_le_bytes());
+
+    for cue_point in cue_points {
+        data.extend_from_slice(&cue_point.id.to_le_bytes());
+        // dwPosition, the same as the sample offset without a playlist.
+        data.extend_from_slice(&cue_point.position.to_le_bytes());
+        data.extend_from_slice(b"data");
+        // dwChunkStart and dwBlockStart.
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&cue_point.position.to_le_bytes());
+    }
+
+    data
+}
+
+/// The LIST chunk data, starting with the "adtl" list type. `None` if no cue point has text.
+pub(super) fn to_adtl_data(cue_points: &[WavCuePoint]) -> Option<Vec<u8>> {
+    let mut data = b"adtl".to_vec();
+
+    for cue_point in cue_points {
+        let id = cue_point.id.to_le_bytes();
+
+        if let Some(label) = &cue_point.label {
+            write_sub_chunk(&mut data, b"labl", &[&id[..], &zstr(label)].concat());
+        }
+        if let Some(note) = &cue_point.note {
+            write_sub_chunk(&mut data, b"note", &[&id[..], &zstr(note)].concat());
+        }
+        if cue_point.length > 0 || cue_point.text.is_some() {
+            let mut ltxt = id.to_vec();
+            ltxt.extend_from_slice(&cue_point.length.to_le_bytes());
+            // dwPurposeID.
+            ltxt.extend_from_slice(b"rgn ");
+            // wCountry, wLanguage, wDialect and wCodePage.
+            ltxt.extend_from_slice(&[0; 8]);
+            ltxt.extend_from_slice(&zstr(cue_point.text.as_deref().unwrap_or_default()));
+
+            write_sub_chunk(&mut data, b"ltxt", &ltxt);
+        }
+    }
+
+    if data.len() == 4 {
+        return None;
+    }
+
+    Some(data)
+}
+
+fn cue_point_mut(cue_points: &mut Vec<WavCuePoint>, id: u32) -> &mut WavCuePoint {
+    let index = match cue_points.iter().position(|cue_point| cue_point.id == id) {
+        Some(index) => index,
+        None => {
+            cue_points.push(WavCuePoint::new(id, 0));
+            cue_points.len() - 1
+        },
+    };
+
+    &mut cue_points[index]
+}
+
+/// Null terminated text.
+fn zstr(text: &str) -> Vec<u8> {
+    let mut data = text.as_bytes().to_vec();
+    data.push(0);
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use crate::{encoder::LgEncoder, SampleType};
+    use crate::wav::fixtures::{info, samples};
+    use crate::wav::{LgWavDecoder, LgWavEncoder, WavContainer};
+    use super::{merge_cue_points, merge_cue_texts, read_adtl, read_cue, to_adtl_data, to_cue_data, WavCuePoint};
+
+    fn cue_points() -> Vec<WavCuePoint> {
+        vec![
+            WavCuePoint {
+                label: Some("Intro".to_string()),
+                note: Some("Quiet".to_string()),
+                ..WavCuePoint::new(1, 0)
+            },
+            // No text at all.
+            WavCuePoint::new(2, 250),
+            WavCuePoint {
+                length: 300,
+                text: Some("Chorus".to_string()),
+                ..WavCuePoint::new(7, 400)
+            },
+        ]
+    }
+
+    fn encode(before_data: bool) -> Vec<u8> {
+        let mut encoder = LgWavEncoder::from_writer(io::Cursor::new(Vec::new()), info(SampleType::INT, 16), WavContainer::RIFF).unwrap();
+        if before_data {
+            encoder.write_cue_points(&cue_points()).unwrap();
+        }
+        encoder.encode_samples(&samples(2000)).unwrap();
+        if !before_data {
+            encoder.write_cue_points(&cue_points()).unwrap();
+        }
+
+        encoder.into_inner().unwrap().into_inner()
+    }
+
+    #[test]
+    fn cue_round_trip() {
+        let file = encode(true);
+        let decoder = LgWavDecoder::from_reader(&file[..]).unwrap();
+        assert_eq!(decoder.cue_points(), cue_points());
+
+        let decoder = LgWavDecoder::from_seekable(io::Cursor::new(encode(false))).unwrap();
+        assert_eq!(decoder.cue_points(), cue_points());
+    }
+
+    #[test]
+    fn adtl_before_cue() {
+        let adtl = to_adtl_data(&cue_points()).unwrap();
+        let cue = to_cue_data(&cue_points());
+
+        let mut merged = Vec::new();
+        merge_cue_texts(&mut merged, read_adtl(&adtl[4..]).unwrap());
+        merge_cue_points(&mut merged, read_cue(&cue).unwrap());
+
+        merged.sort_by_key(|cue_point| cue_point.id);
+        assert_eq!(merged, cue_points());
+    }
+
+    #[test]
+    fn no_adtl_without_text() {
+        assert_eq!(to_adtl_data(&[WavCuePoint::new(1, 10)]), None);
+    }
+}
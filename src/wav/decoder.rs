@@ -1,12 +1,24 @@
-use std::{fmt, fs, io, path};
-use crate::{decoder::LgDecoder, error::Error, AudioInfo, Result, SampleType};
-use super::{reader::LgWavReader, LgWavSampleIter, WavChunks};
+use std::{fmt, fs, io, path, time};
+use crate::{decoder::{check_planar_buffers, LgDecoder}, error::Error, AudioInfo, Result, SampleType};
+use super::{adpcm::AdpcmBlocks, cue, WavFmt, reader::{LgWavReader, UNKNOWN_DATA_SIZE}, LgWavBulkReader, LgWavSampleIter, WavBext, WavCuePoint, WavChunkInfo, WavChunks, WavInstrument, WavMetadata, WavSampler};
 
 pub struct LgWavDecoder<R: io::Read> {
     info: AudioInfo,
     sample_len: usize,
+    valid_bits_per_sample: u16,
+    /// Bytes of a sample frame, not used by ADPCM. It doesn't come from block_align, some files have it wrong or 0.
+    frame_size: usize,
+    bulk: LgWavBulkReader,
 
     reader: LgWavReader<R>,
+    /// Only present for block based formats (ADPCM).
+    blocks: Option<AdpcmBlocks>,
+    skipped_chunks: Vec<WavChunkInfo>,
+    metadata: Option<WavMetadata>,
+    bext: Option<WavBext>,
+    cue_points: Vec<WavCuePoint>,
+    sampler: Option<WavSampler>,
+    instrument: Option<WavInstrument>,
 }
 impl<R: io::Read> fmt::Debug for LgWavDecoder<R> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -19,34 +31,187 @@ impl<R: io::Read> fmt::Debug for LgWavDecoder<R> {
 impl LgWavDecoder<io::BufReader<fs::File>> {
     pub fn new(path: impl AsRef<path::Path>) -> Result<Self> {
         let file = fs::File::open(path)?;
+
+        Self::from_seekable(io::BufReader::new(file))
+    }
+}
+impl<R: io::Read> LgWavDecoder<R> {
+    /// Decodes from any reader, like stdin or a `&[u8]`. Chunks after the audio data are not read, 
+    /// use [`LgWavDecoder::from_seekable`] for readers that can seek.
+    /// 
+    /// Streamed files that didn't know their length are read until the end, [`LgDecoder::len`] is 0 for them.
+    pub fn from_reader(reader: R) -> Result<Self> {
         // Already checks the header.
-        let mut reader = LgWavReader::new(io::BufReader::new(file))?;
-        
+        let mut reader = LgWavReader::new(reader)?;
+        let (fmt, fact_sample_frames, d_len, other_chunks) = Self::read_chunks(&mut reader)?;
+
+        Ok(Self::with_chunks(reader, fmt, d_len, fact_sample_frames, other_chunks))
+    }
+
+    /// Reads every chunk until the data chunk, returns the fmt, the fact sample frames, 
+    /// the data length and the other chunks.
+    fn read_chunks(reader: &mut LgWavReader<R>) -> Result<(WavFmt, Option<u64>, u64, Vec<WavChunks>)> {
         // Just in case the fmt chunk is not present.
-        let mut info = Err(Error::WrongFmt);
-        let sample_len;
+        let mut fmt = Err(Error::WrongFmt);
+        let mut fact_sample_frames = None;
+        // Anything else is handled once the decoder exists.
+        let mut other_chunks = Vec::new();
 
         loop { 
             let chunk = reader.read_next_chunk();
             match chunk? {
-                WavChunks::FMT(wav_info) => info = Ok(wav_info),
-                WavChunks::FACT => (),
-                WavChunks::DATA(d_len) => {
-                    match &mut info {
-                        Ok(info) => sample_len = (d_len / (info.bits_per_sample as u32 / 8)) as usize,
-                        Err(_) => return Err(Error::WrongFmt),
-                    }
-
-                    break;
-                },
+                WavChunks::FMT(wav_fmt) => fmt = Ok(wav_fmt),
+                WavChunks::FACT(sample_frames) => fact_sample_frames = Some(sample_frames),
+                WavChunks::DATA(d_len) => return Ok((fmt?, fact_sample_frames, d_len, other_chunks)),
+                chunk => other_chunks.push(chunk),
             }
         } 
-        
-        Ok(Self {
-            info: info?,
+    }
+
+    fn with_chunks(
+        reader: LgWavReader<R>, 
+        fmt: WavFmt, 
+        d_len: u64, 
+        fact_sample_frames: Option<u64>, 
+        other_chunks: Vec<WavChunks>
+    ) -> Self {
+        let (sample_len, blocks) = fmt.data_samples(d_len, fact_sample_frames);
+
+        let mut decoder = Self {
+            info: fmt.info,
             sample_len,
+            valid_bits_per_sample: fmt.valid_bits_per_sample,
+            frame_size: fmt.info.channels as usize * (fmt.info.bits_per_sample as usize / 8),
+            bulk: LgWavBulkReader::new(&fmt.info, reader.byte_order),
             reader,
-        })
+            blocks,
+            skipped_chunks: Vec::new(),
+            metadata: None,
+            bext: None,
+            cue_points: Vec::new(),
+            sampler: None,
+            instrument: None,
+        };
+        for chunk in other_chunks {
+            decoder.keep_chunk(chunk);
+        }
+
+        decoder
+    }
+}
+impl<R: io::Read> LgWavDecoder<R> {
+    /// Bits of every sample that hold audio, WAVE_FORMAT_EXTENSIBLE files can store them in bigger containers 
+    /// (like 20 bits in 24). [`AudioInfo::bits_per_sample`] is always the container size.
+    pub fn valid_bits_per_sample(&self) -> u16 {
+        self.valid_bits_per_sample
+    }
+
+    /// Chunks that the decoder does not use, in the order they appear.
+    /// Chunks after the audio data are only found when the source can seek.
+    pub fn skipped_chunks(&self) -> &[WavChunkInfo] {
+        &self.skipped_chunks
+    }
+
+    /// LIST-INFO metadata, if the file has any.
+    pub fn metadata(&self) -> Option<&WavMetadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Broadcast Wave Format extension, if the file has one.
+    pub fn bext(&self) -> Option<&WavBext> {
+        self.bext.as_ref()
+    }
+
+    /// Markers from the cue and LIST-adtl chunks, in the order they appear.
+    pub fn cue_points(&self) -> &[WavCuePoint] {
+        &self.cue_points
+    }
+
+    /// Sampler information and loop points, if the file has a smpl chunk.
+    pub fn sampler(&self) -> Option<&WavSampler> {
+        self.sampler.as_ref()
+    }
+
+    /// Key and velocity ranges, if the file has an inst chunk.
+    pub fn instrument(&self) -> Option<&WavInstrument> {
+        self.instrument.as_ref()
+    }
+
+    /// Stores the chunks that are not needed to decode the samples.
+    fn keep_chunk(&mut self, chunk: WavChunks) {
+        match chunk {
+            WavChunks::INFO(info) => self.metadata.get_or_insert_default().merge(info),
+            WavChunks::BEXT(bext) => self.bext = Some(*bext),
+            WavChunks::CUE(cue_points) => cue::merge_cue_points(&mut self.cue_points, cue_points),
+            WavChunks::ADTL(texts) => cue::merge_cue_texts(&mut self.cue_points, texts),
+            WavChunks::SMPL(sampler) => self.sampler = Some(sampler),
+            WavChunks::INST(instrument) => self.instrument = Some(instrument),
+            WavChunks::UNKNOWN(chunk) => self.skipped_chunks.push(chunk),
+            WavChunks::FMT(_) | WavChunks::FACT(_) | WavChunks::DATA(_) => (),
+        }
+    }
+}
+impl<R: io::Read + io::Seek> LgWavDecoder<R> {
+    /// Like [`LgWavDecoder::from_reader`], but also reads the chunks after the audio data, 
+    /// and the length of streamed files that didn't know it.
+    pub fn from_seekable(reader: R) -> Result<Self> {
+        // Already checks the header.
+        let mut reader = LgWavReader::new(reader)?;
+        let (fmt, fact_sample_frames, d_len, other_chunks) = Self::read_chunks(&mut reader)?;
+
+        // Streamed files that didn't know their length have the data until the end of the file, 
+        // and their fact chunk is not right either.
+        let (d_len, fact_sample_frames) = match d_len {
+            UNKNOWN_DATA_SIZE => (
+                reader.data_len_to_end()?,
+                fact_sample_frames.filter(|&frames| frames != 0xFFFFFFFF),
+            ),
+            d_len => (d_len, fact_sample_frames),
+        };
+
+        let mut decoder = Self::with_chunks(reader, fmt, d_len, fact_sample_frames, other_chunks);
+        decoder.read_trailing_chunks()?;
+
+        Ok(decoder)
+    }
+
+    /// Moves to the sample frame `frame`, iterating starts again from its first channel. 
+    /// Seeking past the end leaves nothing to iterate.
+    pub fn seek_frame(&mut self, frame: usize) -> Result<()> {
+        let offset = match &mut self.blocks {
+            Some(blocks) => blocks.seek(frame),
+            None => frame.saturating_mul(self.frame_size),
+        };
+        self.bulk.clear();
+
+        self.reader.seek_data(offset)
+    }
+
+    /// Moves to the sample frame at `time`, see [`LgWavDecoder::seek_frame`].
+    pub fn seek_time(&mut self, time: time::Duration) -> Result<()> {
+        let frame = time.as_nanos() * self.info.sample_rate as u128 / 1_000_000_000;
+
+        self.seek_frame(usize::try_from(frame).unwrap_or(usize::MAX))
+    }
+
+    /// Goes back to the first sample.
+    pub fn rewind(&mut self) -> Result<()> {
+        self.seek_frame(0)
+    }
+
+    /// Many writers put their metadata after the data chunk, this reads it and goes back to the first sample.
+    fn read_trailing_chunks(&mut self) -> Result<()> {
+        self.reader.seek_chunk(self.reader.data_end())?;
+
+        // Whatever comes after the audio is not worth failing over, so any error just stops the search.
+        while let Ok(chunk) = self.reader.read_next_chunk() {
+            match chunk {
+                WavChunks::DATA(_) => break,
+                chunk => self.keep_chunk(chunk),
+            }
+        }
+
+        self.reader.seek_data(0)
     }
 }
 impl<R: io::Read> LgDecoder for LgWavDecoder<R> {
@@ -62,7 +227,23 @@ impl<R: io::Read> LgDecoder for LgWavDecoder<R> {
             None => SampleType::INT,
         };
 
-        LgWavSampleIter::new(&mut self.reader, sample_type, self.info.bits_per_sample)
+        let byte_order = self.reader.byte_order;
+
+        LgWavSampleIter::new(&mut self.reader, self.blocks.as_mut(), sample_type, self.info.bits_per_sample, byte_order)
+    }
+
+    fn read_samples<S: super::Sample>(&mut self, buffer: &mut [S]) -> Result<usize> {
+        self.bulk.read_samples(&mut self.reader, self.blocks.as_mut(), buffer)
+    }
+
+    fn decode_planar<S: super::Sample, C: AsMut<[S]>>(&mut self, buffers: &mut [C]) -> Result<usize> {
+        check_planar_buffers(buffers.len(), self.info.channels)?;
+
+        self.bulk.decode_planar(&mut self.reader, self.blocks.as_mut(), buffers)
+    }
+
+    fn planar_samples<S: super::Sample>(&mut self) -> Vec<Vec<S>> {
+        self.bulk.planar_samples(&mut self.reader, self.blocks.as_mut(), self.info.channels as usize)
     }
 
     #[inline(always)]
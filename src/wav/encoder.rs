@@ -1,7 +1,7 @@
 use std::{fs, io, path};
 
 use crate::{encoder::LgEncoder, Result, SampleType, AudioInfo};
-use super::writer::LgWavWriter;
+use super::{cue, writer::{LgWavStreamWriter, LgWavWriter}, WavBext, WavContainer, WavCuePoint, WavInstrument, WavMetadata, WavSampler};
 
 pub struct LgWavEncoder<W: io::Write + io::Seek> {
     pub(super) info: AudioInfo,
@@ -9,15 +9,28 @@ pub struct LgWavEncoder<W: io::Write + io::Seek> {
 }
 impl LgWavEncoder<io::BufWriter<fs::File>> {
     pub fn new(path: impl AsRef<path::Path>, info: AudioInfo) -> Result<Self> {
+        Self::with_container(path, info, WavContainer::RIFF)
+    }
+
+    /// Use [`WavContainer::RF64`] for files that can be bigger than 4 GiB.
+    pub fn with_container(path: impl AsRef<path::Path>, info: AudioInfo, container: WavContainer) -> Result<Self> {
         let file = fs::File::create(path)?;
-        let writer = LgWavWriter::new(io::BufWriter::new(file), &info)?;
+        
+        Self::from_writer(io::BufWriter::new(file), info, container)
+    }
+}
+impl<W: io::Write + io::Seek> LgWavEncoder<W> {
+    /// Encodes into any writer that can seek, like an `io::Cursor<Vec<u8>>` or a file that is already open.
+    /// The file starts wherever the writer is, so it can follow other data.
+    pub fn from_writer(writer: W, info: AudioInfo, container: WavContainer) -> Result<Self> {
+        let writer = LgWavWriter::new(writer, &info, container)?;
 
         Ok(Self {
             info,
             writer,
         })
     }
-    
+
     pub fn flush(&mut self) -> Result<()> {
         self.writer.flush()
     }
@@ -25,6 +38,47 @@ impl LgWavEncoder<io::BufWriter<fs::File>> {
     pub fn finish(mut self) -> Result<()> {
         self.writer.finish()
     }
+
+    /// Finishes the file and returns the writer, positioned at the end of it.
+    pub fn into_inner(self) -> Result<W> {
+        self.writer.into_inner()
+    }
+}
+impl<W: io::Write + io::Seek> LgWavEncoder<W> {
+    /// Writes a LIST-INFO chunk. It goes before the audio data if no sample was encoded yet, 
+    /// otherwise it is written after the audio data when finishing.
+    pub fn write_metadata(&mut self, metadata: &WavMetadata) -> Result<()> {
+        self.writer.write_chunk(b"LIST", &metadata.to_list_data())
+    }
+
+    /// Writes a Broadcast Wave Format bext chunk. Like [`LgWavEncoder::write_metadata`] it goes before
+    /// the audio data unless samples were already encoded.
+    pub fn write_bext(&mut self, bext: &WavBext) -> Result<()> {
+        self.writer.write_chunk(b"bext", &bext.to_chunk_data())
+    }
+
+    /// Writes a cue chunk, and a LIST-adtl chunk when any cue point has text. 
+    /// Like [`LgWavEncoder::write_metadata`] they go before the audio data unless samples were already encoded.
+    pub fn write_cue_points(&mut self, cue_points: &[WavCuePoint]) -> Result<()> {
+        self.writer.write_chunk(b"cue ", &cue::to_cue_data(cue_points))?;
+
+        match cue::to_adtl_data(cue_points) {
+            Some(adtl) => self.writer.write_chunk(b"LIST", &adtl),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes a smpl chunk. Like [`LgWavEncoder::write_metadata`] it goes before the audio data 
+    /// unless samples were already encoded.
+    pub fn write_sampler(&mut self, sampler: &WavSampler) -> Result<()> {
+        self.writer.write_chunk(b"smpl", &sampler.to_chunk_data())
+    }
+
+    /// Writes an inst chunk. Like [`LgWavEncoder::write_metadata`] it goes before the audio data 
+    /// unless samples were already encoded.
+    pub fn write_instrument(&mut self, instrument: &WavInstrument) -> Result<()> {
+        self.writer.write_chunk(b"inst", &instrument.to_chunk_data())
+    }
 }
 impl<W: io::Write + io::Seek>  LgEncoder for LgWavEncoder<W> {
     #[inline(always)]
@@ -39,12 +93,24 @@ impl<W: io::Write + io::Seek>  LgEncoder for LgWavEncoder<W> {
             None => SampleType::INT,
         };
 
-        self.writer.write_sample(sample, sample_type, self.info.bits_per_sample)
+        let bits_per_sample = self.info.bits_per_sample;
+
+        self.writer.write_sample(sample, sample_type, bits_per_sample)
     }
     
+    fn encode_samples<S: crate::Sample + Copy>(&mut self, samples: &[S]) -> Result<()> {
+        let sample_type = match self.info.sample_type {
+            Some(st) => st,
+            None => SampleType::INT,
+        };
+        let bits_per_sample = self.info.bits_per_sample;
+
+        self.writer.write_samples(samples, sample_type, bits_per_sample)
+    }
+
     #[inline(always)]
     fn encoded_samples(&self) -> usize {
-        self.writer.data_bytes_written as usize * self.info.bits_per_sample as usize
+        self.writer.samples_written as usize
     }
     
     #[inline(always)]
@@ -54,7 +120,81 @@ impl<W: io::Write + io::Seek>  LgEncoder for LgWavEncoder<W> {
     
     #[inline(always)]
     fn len(&self) -> usize {
-        self.writer.data_bytes_written as usize / self.info.bits_per_sample as usize
+        self.writer.samples_written as usize
     }
 
-}
\ No newline at end of file
+}
+
+/// Encodes WAV files to outputs that can not seek, like stdout or a socket. 
+/// Metadata chunks are not supported, the header is written before any sample.
+pub struct LgWavStreamEncoder<W: io::Write> {
+    pub(super) info: AudioInfo,
+    writer: LgWavStreamWriter<W>,
+}
+impl<W: io::Write> LgWavStreamEncoder<W> {
+    /// The length is unknown, decoders read the audio data until the end of the file.
+    pub fn new(writer: W, info: AudioInfo) -> Result<Self> {
+        Ok(Self {
+            writer: LgWavStreamWriter::new(writer, &info, None)?,
+            info,
+        })
+    }
+
+    /// `sample_len` is the number of samples (not frames) that will be encoded, 
+    /// encoding more or finishing with less is an error.
+    pub fn with_len(writer: W, info: AudioInfo, sample_len: usize) -> Result<Self> {
+        Ok(Self {
+            writer: LgWavStreamWriter::new(writer, &info, Some(sample_len as u64))?,
+            info,
+        })
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        self.writer.finish()
+    }
+}
+impl<W: io::Write> LgEncoder for LgWavStreamEncoder<W> {
+    #[inline(always)]
+    fn info(&self) -> AudioInfo {
+        self.info
+    }
+
+    #[inline(always)]
+    fn encode_sample<S: crate::Sample>(&mut self, sample: S) -> Result<()> {
+        let sample_type = match self.info.sample_type {
+            Some(st) => st,
+            None => SampleType::INT,
+        };
+
+        self.writer.write_sample(sample, sample_type, self.info.bits_per_sample)
+    }
+
+    fn encode_samples<S: crate::Sample + Copy>(&mut self, samples: &[S]) -> Result<()> {
+        let sample_type = match self.info.sample_type {
+            Some(st) => st,
+            None => SampleType::INT,
+        };
+        let bits_per_sample = self.info.bits_per_sample;
+
+        self.writer.write_samples(samples, sample_type, bits_per_sample)
+    }
+
+    #[inline(always)]
+    fn encoded_samples(&self) -> usize {
+        self.writer.samples_written as usize
+    }
+
+    #[inline(always)]
+    fn duration(&self) -> usize {
+        self.len() / self.info.channels as usize / self.info.sample_rate as usize
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.writer.samples_written as usize
+    }
+}
@@ -0,0 +1,32 @@
+//! Helpers shared by the tests of the WAV and Wave64 modules.
+use std::io;
+use crate::{decoder::LgDecoder, encoder::LgEncoder, AudioInfo, SampleType};
+use super::{LgWavDecoder, LgWavEncoder, WavContainer};
+
+/// Stereo at 8000 Hz.
+pub(crate) fn info(sample_type: SampleType, bits_per_sample: u16) -> AudioInfo {
+    AudioInfo {
+        channels: 2,
+        sample_rate: 8000,
+        bits_per_sample,
+        sample_type: Some(sample_type),
+        channel_layout: None,
+    }
+}
+
+/// `len` samples spread over the whole 16 bit range.
+pub(crate) fn samples(len: usize) -> Vec<i16> {
+    (0..len as i32).map(|i| ((i * 7919) % 65536 - 32768) as i16).collect()
+}
+
+/// The whole file of `samples` encoded in memory.
+pub(crate) fn encode(info: AudioInfo, container: WavContainer, samples: &[i16]) -> Vec<u8> {
+    let mut encoder = LgWavEncoder::from_writer(io::Cursor::new(Vec::new()), info, container).unwrap();
+    encoder.encode_samples(samples).unwrap();
+
+    encoder.into_inner().unwrap().into_inner()
+}
+
+pub(crate) fn decode(file: Vec<u8>) -> Vec<i16> {
+    LgWavDecoder::from_seekable(io::Cursor::new(file)).unwrap().samples().collect()
+}
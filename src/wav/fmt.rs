@@ -0,0 +1,666 @@
+use std::io::{self, Write};
+use crate::{error::Error, reader::LgReader, writer::LgWriter, AudioInfo, ByteOrder, ChannelLayout, Result, SampleType};
+use super::adpcm::{self, AdpcmBlocks};
+use super::reader::UNKNOWN_DATA_SIZE;
+use super::{guid_to_string, sub_format_guid, sub_format_tag, WavFmt, WavFmtTag};
+use super::{WAVE_FORMAT_ALAW, WAVE_FORMAT_EXTENSIBLE, WAVE_FORMAT_IEEE_FLOAT, WAVE_FORMAT_IMA_ADPCM, WAVE_FORMAT_MULAW, WAVE_FORMAT_PCM};
+
+// ------------------------- READ --------------------------
+
+impl WavFmt {
+    /// Reads the fmt chunk data, it is the same for every container. Only RIFX files are BIG_ENDIAN.
+    pub(crate) fn read(data: &[u8], byte_order: ByteOrder) -> Result<Self> {
+        let ck_size = data.len();
+        let mut reader = FmtReader { data, byte_order };
+
+        if ck_size < 16 { return Err(Error::WrongFmt); }
+
+        let fmt_tag: WavFmtTag = reader.read_u16()?.into();
+        let channels = reader.read_u16()?;
+        let samples_per_sec = reader.read_u32()?;
+        let _avg_bytes_per_sec = reader.read_u32()?;
+        let block_align = reader.read_u16()?;
+        let bits_per_sample = reader.read_u16()?;
+
+        let info = AudioInfo {
+            channels,
+            sample_rate: samples_per_sec,
+            bits_per_sample,
+            sample_type: Some(match fmt_tag {
+                WavFmtTag::WAVE_FORMAT_PCM 
+                | WavFmtTag::WAVE_FORMAT_EXTENSIBLE
+                | WavFmtTag::OTHER(_) => SampleType::INT,
+                WavFmtTag::WAVE_FORMAT_IEEE_FLOAT => SampleType::FLOAT,
+                WavFmtTag::WAVE_FORMAT_ALAW => SampleType::ALAW,
+                WavFmtTag::WAVE_FORMAT_MULAW => SampleType::MULAW,
+                WavFmtTag::WAVE_FORMAT_IMA_ADPCM => SampleType::IMA_ADPCM,
+                WavFmtTag::WAVE_FORMAT_ADPCM => SampleType::MS_ADPCM,
+            }),
+            channel_layout: None,
+        };
+
+        let mut fmt = WavFmt {
+            info,
+            block_align,
+            valid_bits_per_sample: bits_per_sample,
+            samples_per_block: 0,
+            coefficients: Vec::new(),
+        };
+
+        // Time to check if the info is ok.
+        check_fmt(&fmt)?;
+
+        // The ADPCM block headers are only defined in little endian.
+        if byte_order == ByteOrder::BIG_ENDIAN && matches!(info.sample_type, Some(SampleType::IMA_ADPCM | SampleType::MS_ADPCM)) {
+            return Err(Error::WrongFmtInfo("ADPCM formats are not supported in big endian files!".to_string()));
+        }
+
+        let result = match (fmt_tag, ck_size) {
+            (WavFmtTag::WAVE_FORMAT_PCM, ck_size) => reader.read_check_fmt_pcm(ck_size, &fmt.info),
+            (WavFmtTag::WAVE_FORMAT_IEEE_FLOAT, ck_size) => reader.read_check_fmt_ieee_float(ck_size, &fmt.info),
+            (WavFmtTag::WAVE_FORMAT_ALAW, ck_size) => reader.read_check_fmt_alaw(ck_size, &fmt.info),
+            (WavFmtTag::WAVE_FORMAT_MULAW, ck_size) => reader.read_check_fmt_mulaw(ck_size, &fmt.info),
+            (WavFmtTag::WAVE_FORMAT_IMA_ADPCM, ck_size) => reader.read_check_fmt_ima_adpcm(ck_size, &mut fmt),
+            (WavFmtTag::WAVE_FORMAT_ADPCM, ck_size) => reader.read_check_fmt_ms_adpcm(ck_size, &mut fmt),
+            (WavFmtTag::WAVE_FORMAT_EXTENSIBLE, ck_size) => reader.read_check_fmt_extensible(ck_size, &mut fmt),
+
+            _ => return Err(Error::WrongFmt),
+        };
+
+        // Whatever is left is ignored, some writers add extra bytes to the fmt chunk.
+        match result {
+            Err(Error::Io(_)) => Err(Error::WrongFmtInfo("fmt chunk is smaller than its fmt_tag requires!".to_string())),
+            result => result.map(|_| fmt),
+        }
+    }
+}
+
+impl WavFmt {
+    /// Number of interleaved samples in a data chunk of `data_len` bytes, and the block decoder 
+    /// for ADPCM formats. `fact_sample_frames` comes from the fact chunk, if there is one.
+    pub(crate) fn data_samples(&self, data_len: u64, fact_sample_frames: Option<u64>) -> (usize, Option<AdpcmBlocks>) {
+        let is_adpcm = matches!(self.info.sample_type, Some(SampleType::IMA_ADPCM | SampleType::MS_ADPCM));
+
+        // Streamed files that didn't know their length, the samples go until the end of the file.
+        if data_len == UNKNOWN_DATA_SIZE {
+            return (0, is_adpcm.then(|| AdpcmBlocks::new(self, usize::MAX, usize::MAX)));
+        }
+
+        if !is_adpcm {
+            return ((data_len / (self.info.bits_per_sample as u64 / 8)) as usize, None);
+        }
+
+        // Blocks are padded, so the fact chunk is the only way of knowing the real length.
+        let sample_len = match fact_sample_frames {
+            Some(frames) => frames as usize * self.info.channels as usize,
+            None => AdpcmBlocks::sample_len(self, data_len as usize),
+        };
+
+        (sample_len, Some(AdpcmBlocks::new(self, data_len as usize, sample_len)))
+    }
+}
+
+/// Reads the extension of the fmt chunk, depending on the fmt_tag.
+struct FmtReader<'d> {
+    data: &'d [u8],
+    byte_order: ByteOrder,
+}
+// LgReader comes with it.
+impl io::Read for FmtReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.data.read(buf)
+    }
+}
+impl FmtReader<'_> {
+    fn read_u16(&mut self) -> Result<u16> {
+        match self.byte_order {
+            ByteOrder::LITTLE_ENDIAN => self.read_le_u16(),
+            ByteOrder::BIG_ENDIAN => self.read_be_u16(),
+        }
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        match self.byte_order {
+            ByteOrder::LITTLE_ENDIAN => self.read_le_u32(),
+            ByteOrder::BIG_ENDIAN => self.read_be_u32(),
+        }
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        match self.byte_order {
+            ByteOrder::LITTLE_ENDIAN => self.read_le_i16(),
+            ByteOrder::BIG_ENDIAN => self.read_be_i16(),
+        }
+    }
+    
+    fn read_check_fmt_pcm(&mut self, ck_size :usize, fmt: &AudioInfo) -> Result<()> {
+        // If ck_size is 16, that means that all the fmt was read.
+        if ck_size == 16 { return Ok(()); }
+
+        // If this executes then it means that is a WAVEFORMATEX.
+        // Dealing with cb_size.
+        self.skip_next_bytes::<2>()?;
+        
+        // Dealing with bits_per_sample.
+        if fmt.bits_per_sample > 24 || fmt.bits_per_sample < 8 { 
+            return Err(Error::WrongFmtInfo("Invalid bits_per_sample for PCM format!".to_string()));
+        }
+        
+        Ok(())
+    }
+
+    fn read_check_fmt_ieee_float(&mut self, ck_size :usize, _: &AudioInfo) -> Result<()> {
+        // If ck_size is 16, that means that all the fmt was read.
+        if ck_size == 16 { return Ok(()); }
+        if ck_size != 18 { 
+            return Err(Error::WrongFmtInfo("IEEE_FLOAT does not alow for ck_size > 18!".to_string())); 
+        }
+
+        // Dealing with cb_size.
+        if self.read_u16()? != 0 {
+            return Err(Error::WrongFmtInfo("IEEE_FLOAT must have cb_size of 0!".to_string()));
+        }
+
+        Ok(())
+    }
+    
+    fn read_check_fmt_alaw(&mut self, ck_size :usize, fmt: &AudioInfo) -> Result<()> {
+        if fmt.bits_per_sample != 8 {
+            return Err(Error::WrongFmtInfo("ALAW must have 8 bits_per_sample!".to_string()));
+        }
+
+        // If ck_size is 16, that means that all the fmt was read.
+        if ck_size == 16 { return Ok(()); }
+        
+        // Dealing with cb_size.
+        if ck_size != 18 || self.read_u16()? != 0 {
+            return Err(Error::WrongFmtInfo("ALAW must have cb_size of 0!".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn read_check_fmt_mulaw(&mut self, ck_size :usize, fmt: &AudioInfo) -> Result<()> {
+        if fmt.bits_per_sample != 8 {
+            return Err(Error::WrongFmtInfo("MULAW must have 8 bits_per_sample!".to_string()));
+        }
+
+        // If ck_size is 16, that means that all the fmt was read.
+        if ck_size == 16 { return Ok(()); }
+        
+        // Dealing with cb_size.
+        if ck_size != 18 || self.read_u16()? != 0 {
+            return Err(Error::WrongFmtInfo("MULAW must have cb_size of 0!".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn read_check_fmt_ima_adpcm(&mut self, ck_size :usize, fmt: &mut WavFmt) -> Result<()> {
+        if fmt.info.bits_per_sample != 4 {
+            return Err(Error::WrongFmtInfo("IMA_ADPCM must have 4 bits_per_sample!".to_string()));
+        }
+
+        if ck_size < 20 {
+            return Err(Error::WrongFmtInfo("IMA_ADPCM must have ck_size of at least 20!".to_string()));
+        }
+        
+        // Dealing with cb_size.
+        let cb_size = self.read_u16()? as usize;
+        if cb_size < 2 || ck_size < 18 + cb_size {
+            return Err(Error::WrongFmtInfo("IMA_ADPCM must have cb_size of at least 2!".to_string()));
+        }
+
+        fmt.samples_per_block = self.read_u16()?;
+        
+        // Anything else in the extension is not needed.
+        self.skip_bytes(cb_size - 2)?;
+
+        // check_fmt made sure block_align is bigger than the header.
+        let header_size = 4 * fmt.info.channels as usize;
+        let block_align = fmt.block_align as usize;
+        let samples_per_block = fmt.samples_per_block as usize;
+        if samples_per_block == 0 
+            || samples_per_block > (block_align - header_size) * 2 / fmt.info.channels as usize + 1 
+        {
+            return Err(Error::WrongFmtInfo("IMA_ADPCM block_align and samples_per_block do not match!".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn read_check_fmt_ms_adpcm(&mut self, ck_size :usize, fmt: &mut WavFmt) -> Result<()> {
+        if fmt.info.bits_per_sample != 4 {
+            return Err(Error::WrongFmtInfo("MS_ADPCM must have 4 bits_per_sample!".to_string()));
+        }
+
+        if ck_size < 22 {
+            return Err(Error::WrongFmtInfo("MS_ADPCM must have ck_size of at least 22!".to_string()));
+        }
+        
+        // Dealing with cb_size.
+        let cb_size = self.read_u16()? as usize;
+        if cb_size < 4 || ck_size < 18 + cb_size {
+            return Err(Error::WrongFmtInfo("MS_ADPCM must have cb_size of at least 4!".to_string()));
+        }
+
+        fmt.samples_per_block = self.read_u16()?;
+        let num_coef = self.read_u16()? as usize;
+        if num_coef == 0 || cb_size < 4 + num_coef * 4 {
+            return Err(Error::WrongFmtInfo("MS_ADPCM cb_size is too small for its coefficients!".to_string()));
+        }
+
+        fmt.coefficients = (0..num_coef)
+            .map(|_| Ok((self.read_i16()?, self.read_i16()?)))
+            .collect::<Result<_>>()?;
+
+        // Anything else in the extension is not needed.
+        self.skip_bytes(cb_size - 4 - num_coef * 4)?;
+
+        // check_fmt made sure block_align can hold the header.
+        let header_size = 7 * fmt.info.channels as usize;
+        let block_align = fmt.block_align as usize;
+        let samples_per_block = fmt.samples_per_block as usize;
+        if samples_per_block < 2 
+            || samples_per_block > (block_align - header_size) * 2 / fmt.info.channels as usize + 2 
+        {
+            return Err(Error::WrongFmtInfo("MS_ADPCM block_align and samples_per_block do not match!".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn read_check_fmt_extensible(&mut self, ck_size :usize, fmt: &mut WavFmt) -> Result<()> {
+        if ck_size < 40 {
+            return Err(Error::WrongFmtInfo("WAVE_FORMAT_EXTENSIBLE must have ck_size of 40!".to_string()));
+        }
+        
+        // Dealing with cb_size.
+        if self.read_u16()? != 22 {
+            return Err(Error::WrongFmtInfo("WAVE_FORMAT_EXTENSIBLE must have cb_size of 22!".to_string()));
+        }
+        
+        let valid_bits_per_sample = self.read_u16()?;
+        let channel_mask = self.read_u32()?;
+        fmt.info.channel_layout = Some(ChannelLayout::from_mask(channel_mask));
+        // GUID
+        let sub_format: [u8; 16] = self.read_next_bytes()?;
+
+        fmt.info.sample_type = Some(match sub_format_tag(&sub_format) {
+            Some(WavFmtTag::WAVE_FORMAT_PCM) => SampleType::INT,
+            Some(WavFmtTag::WAVE_FORMAT_IEEE_FLOAT) => SampleType::FLOAT,
+            Some(WavFmtTag::WAVE_FORMAT_ALAW) => SampleType::ALAW,
+            Some(WavFmtTag::WAVE_FORMAT_MULAW) => SampleType::MULAW,
+
+            _ => return Err(Error::WrongFmtInfo(std::format!(
+                "WAVE_FORMAT_EXTENSIBLE sub_format {} is not supported!", 
+                guid_to_string(&sub_format)
+            ))),
+        });
+
+        // The samples are still read with the container size, the low bits are just padding.
+        if valid_bits_per_sample > fmt.info.bits_per_sample {
+            return Err(Error::WrongFmtInfo(std::format!(
+                "valid_bits_per_sample ({}) can not be bigger than bits_per_sample ({})!", 
+                valid_bits_per_sample, 
+                fmt.info.bits_per_sample
+            )));
+        }
+        if valid_bits_per_sample > 0 {
+            fmt.valid_bits_per_sample = valid_bits_per_sample;
+        }
+
+        Ok(())
+    }
+
+    fn skip_bytes(&mut self, n: usize) -> Result<()> {
+        if n > self.data.len() {
+            return Err(Error::Io(io::Error::new::<String>(io::ErrorKind::UnexpectedEof, "".into())));
+        }
+        self.data = &self.data[n..];
+
+        Ok(())
+    }
+}
+
+fn check_fmt(fmt: &WavFmt) -> Result<()> {
+    let info = &fmt.info;
+    check_info(info)?;
+    
+    // The rest of the ADPCM formats is checked when reading their extension.
+    match info.sample_type {
+        // IMA_ADPCM blocks need at least a byte after the headers, MS_ADPCM ones store two frames in them.
+        Some(SampleType::IMA_ADPCM) => check_adpcm_block_align(fmt, 4 * info.channels as usize + 1, "IMA_ADPCM"),
+        Some(SampleType::MS_ADPCM) => check_adpcm_block_align(fmt, 7 * info.channels as usize, "MS_ADPCM"),
+        _ => Ok(()),
+    }
+}
+
+/// The checks of [`check_fmt`] that don't need the fmt chunk, writers use it before writing anything.
+pub(crate) fn check_info(info: &AudioInfo) -> Result<()> {
+    if info.channels == 0 {
+        return Err(Error::WrongFmtInfo("fmt.channels must be > 0!".to_string()));
+    }
+    if info.sample_rate == 0 {
+        return Err(Error::WrongFmtInfo("fmt.sample_rate must be > 0!".to_string()));
+    }
+    
+    // ADPCM formats have their own bits_per_sample.
+    if matches!(info.sample_type, Some(SampleType::IMA_ADPCM | SampleType::MS_ADPCM)) {
+        return Ok(());
+    }
+    
+    if !info.bits_per_sample.is_multiple_of(8) || info.bits_per_sample == 0 {
+        return Err(Error::WrongFmtInfo("bits_per_sample must be non 0 and a multiple of 8!".to_string()));
+    }
+
+    Ok(())
+}
+
+/// ADPCM blocks start with a header for every channel, so they can't be smaller than `min_block_align`.
+fn check_adpcm_block_align(fmt: &WavFmt, min_block_align: usize, name: &str) -> Result<()> {
+    if (fmt.block_align as usize) < min_block_align {
+        return Err(Error::WrongFmtInfo(std::format!("{} block_align is too small for its channels!", name)));
+    }
+
+    Ok(())
+}
+
+// ------------------------- WRITE --------------------------
+
+/// The fmt chunk data for `info`, it is the same for every container. Only RIFX files are BIG_ENDIAN.
+pub(crate) fn fmt_chunk_data(info: &AudioInfo, byte_order: ByteOrder) -> Result<Vec<u8>> {
+    let mut writer = FmtWriter { 
+        writer: io::Cursor::new(Vec::new()),
+        byte_order,
+    };
+    writer.write_fmt_chunk(info)?;
+
+    Ok(writer.writer.into_inner())
+}
+
+/// Formats that are not plain samples need a fact chunk with the number of sample frames.
+pub(crate) fn needs_fact_chunk(info: &AudioInfo) -> bool {
+    matches!(info.sample_type, Some(SampleType::ALAW | SampleType::MULAW | SampleType::IMA_ADPCM))
+}
+
+struct FmtWriter {
+    writer: io::Cursor<Vec<u8>>,
+    byte_order: ByteOrder,
+}
+impl FmtWriter {
+    fn write_u16(&mut self, data: u16) -> Result<()> {
+        match self.byte_order {
+            ByteOrder::LITTLE_ENDIAN => self.writer.write_le_u16(data),
+            ByteOrder::BIG_ENDIAN => self.writer.write_be_u16(data),
+        }
+    }
+
+    fn write_u32(&mut self, data: u32) -> Result<()> {
+        match self.byte_order {
+            ByteOrder::LITTLE_ENDIAN => self.writer.write_le_u32(data),
+            ByteOrder::BIG_ENDIAN => self.writer.write_be_u32(data),
+        }
+    }
+
+    fn write_fmt_chunk(&mut self, info: &AudioInfo) -> Result<()> {
+        match info.sample_type {
+            Some(SampleType::INT) 
+            | None => if needs_extensible(info) {
+                self.write_check_extensible_fmt(info, WAVE_FORMAT_PCM)
+            }
+            else {
+                self.write_check_pcm_fmt(info)
+            },
+
+            Some(SampleType::FLOAT) => if needs_extensible(info) {
+                self.write_check_extensible_fmt(info, WAVE_FORMAT_IEEE_FLOAT)
+            }
+            else {
+                self.write_check_ieee_float_fmt(info)
+            },
+            Some(SampleType::ALAW) => self.write_check_g711_fmt(info, WAVE_FORMAT_ALAW),
+            Some(SampleType::MULAW) => self.write_check_g711_fmt(info, WAVE_FORMAT_MULAW),
+            Some(SampleType::IMA_ADPCM) if self.byte_order == ByteOrder::BIG_ENDIAN => Err(Error::WrongFmtInfo(
+                "IMA_ADPCM can not be written to big endian files!".to_string()
+            )),
+            Some(SampleType::IMA_ADPCM) => self.write_check_ima_adpcm_fmt(info),
+            Some(SampleType::MS_ADPCM) => Err(Error::WrongFmtInfo("MS_ADPCM encoding is not supported!".to_string())),
+        }
+    }
+    
+    fn write_check_pcm_fmt(&mut self, info: &AudioInfo) -> Result<()> {
+        // fmt_tag.
+        self.write_u16(WAVE_FORMAT_PCM)?;
+
+        self.write_fmt(info)
+    }
+
+    /// `sub_format` is the fmt_tag that goes in the sub_format GUID.
+    fn write_check_extensible_fmt(&mut self, info: &AudioInfo, sub_format: u16) -> Result<()> {
+        // fmt_tag.
+        self.write_u16(WAVE_FORMAT_EXTENSIBLE)?;
+
+        self.write_fmt(info)?;
+            
+        // cb_size.
+        self.write_u16(22)?;
+        
+        // valid_bits_per_sample.
+        self.write_u16(info.bits_per_sample)?;
+        
+        // channel_mask.
+        let layout = info.layout();
+        if layout.len() > info.channels as usize {
+            return Err(Error::WrongFmtInfo("channel_layout has more speakers than channels!".to_string()));
+        }
+        self.write_u32(layout.mask())?;
+        
+        // sub_format.
+        self.writer.write_all(&sub_format_guid(sub_format))?;
+        
+        Ok(())
+    }
+
+    fn write_check_ieee_float_fmt(&mut self, info: &AudioInfo) -> Result<()> {
+        // fmt_tag.
+        self.write_u16(WAVE_FORMAT_IEEE_FLOAT)?;
+        
+        self.write_fmt(info)?;
+        
+        // cb_size.
+        self.write_u16(0)
+    }
+    
+    /// ALAW and MULAW share the same fmt layout.
+    fn write_check_g711_fmt(&mut self, info: &AudioInfo, fmt_tag: u16) -> Result<()> {
+        if info.bits_per_sample != 8 {
+            return Err(Error::WrongFmtInfo("ALAW and MULAW must have 8 bits_per_sample!".to_string()));
+        }
+
+        // The channel_mask only fits in WAVE_FORMAT_EXTENSIBLE, with fmt_tag as the sub_format.
+        if needs_extensible(info) {
+            return self.write_check_extensible_fmt(info, fmt_tag);
+        }
+
+        // fmt_tag.
+        self.write_u16(fmt_tag)?;
+        
+        self.write_fmt(info)?;
+        
+        // cb_size.
+        self.write_u16(0)
+    }
+    
+    fn write_check_ima_adpcm_fmt(&mut self, info: &AudioInfo) -> Result<()> {
+        if info.bits_per_sample != 4 {
+            return Err(Error::WrongFmtInfo("IMA_ADPCM must have 4 bits_per_sample!".to_string()));
+        }
+
+        // There is no WAVE_FORMAT_EXTENSIBLE sub_format for IMA_ADPCM, so there is nowhere to put a channel_mask.
+        if info.layout() != ChannelLayout::default_for(info.channels) {
+            return Err(Error::WrongFmtInfo("IMA_ADPCM can only be written with the default channel_layout!".to_string()));
+        }
+
+        let block_align = adpcm::ima_block_align(info.channels, info.sample_rate)?;
+        let samples_per_block = adpcm::ima_samples_per_block(info.channels, block_align)?;
+
+        // fmt_tag.
+        self.write_u16(WAVE_FORMAT_IMA_ADPCM)?;
+
+        // n_channels.
+        self.write_u16(info.channels)?;
+
+        // samples_per_sec.
+        self.write_u32(info.sample_rate)?;
+        
+        // avg_bytes_per_sec.
+        let bytes_per_sec = info.sample_rate as u64 * block_align as u64 / samples_per_block as u64;
+        self.write_u32(bytes_per_sec as u32)?;
+        
+        // block_align.
+        self.write_u16(block_align)?;
+            
+        // bits_per_sample.
+        self.write_u16(info.bits_per_sample)?;
+        
+        // cb_size.
+        self.write_u16(2)?;
+        
+        // samples_per_block.
+        self.write_u16(samples_per_block)
+    }
+    
+    fn write_fmt(&mut self, info: &AudioInfo) -> Result<()> {
+        // n_channels.
+        self.write_u16(info.channels)?;
+
+        // samples_per_sec.
+        self.write_u32(info.sample_rate)?;
+        
+        // avg_bytes_per_sec.
+        let bytes_per_sec = info.sample_rate
+            * (info.bits_per_sample / 8) as u32
+            * info.channels as u32;
+
+        self.write_u32(bytes_per_sec)?;
+        
+        // block_align.
+        self.write_u16((bytes_per_sec / info.sample_rate) as u16)?;
+            
+        // bits_per_sample.
+        self.write_u16(info.bits_per_sample)
+    }
+}
+
+/// Plain fmt chunks can only describe mono and stereo with their default layouts.
+fn needs_extensible(info: &AudioInfo) -> bool {
+    info.channels > 2 || info.layout() != ChannelLayout::default_for(info.channels)
+}
+#[cfg(test)]
+mod tests {
+    use crate::{wav::{reader::LgWavReader, LgWavSampleIter, WavChunks, WavFmt}, AudioInfo, ByteOrder, ChannelLayout, Result, Sample, SampleType};
+    use crate::wav::fixtures::info;
+    use super::{fmt_chunk_data, sub_format_guid, WAVE_FORMAT_ALAW, WAVE_FORMAT_EXTENSIBLE, WAVE_FORMAT_PCM};
+
+    /// A mono WAVE_FORMAT_EXTENSIBLE PCM file, the samples are the top `bits_per_sample` bits of every i32.
+    fn extensible_file(bits_per_sample: u16, valid_bits_per_sample: u16, samples: &[i32]) -> Vec<u8> {
+        let bytes_per_sample = bits_per_sample as usize / 8;
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()[4 - bytes_per_sample..].to_vec()).collect();
+
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&WAVE_FORMAT_EXTENSIBLE.to_le_bytes());
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&48000u32.to_le_bytes());
+        fmt.extend_from_slice(&(48000 * bytes_per_sample as u32).to_le_bytes());
+        fmt.extend_from_slice(&(bytes_per_sample as u16).to_le_bytes());
+        fmt.extend_from_slice(&bits_per_sample.to_le_bytes());
+        fmt.extend_from_slice(&22u16.to_le_bytes());
+        fmt.extend_from_slice(&valid_bits_per_sample.to_le_bytes());
+        fmt.extend_from_slice(&4u32.to_le_bytes());
+        fmt.extend_from_slice(&sub_format_guid(WAVE_FORMAT_PCM));
+
+        let mut file = b"RIFF".to_vec();
+        file.extend_from_slice(&((4 + 8 + fmt.len() + 8 + data.len()) as u32).to_le_bytes());
+        file.extend_from_slice(b"WAVEfmt ");
+        file.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        file.extend_from_slice(&fmt);
+        file.extend_from_slice(b"data");
+        file.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        file.extend_from_slice(&data);
+
+        file
+    }
+
+    /// The fmt chunk and the samples of `file`.
+    fn read_file(file: &[u8]) -> Result<(WavFmt, Vec<i32>)> {
+        let mut reader = LgWavReader::new(file)?;
+        let mut fmt = None;
+
+        loop {
+            match reader.read_next_chunk()? {
+                WavChunks::FMT(wav_fmt) => fmt = Some(wav_fmt),
+                WavChunks::DATA(_) => break,
+                _ => (),
+            }
+        }
+
+        let fmt = fmt.unwrap();
+        let samples = LgWavSampleIter::new(&mut reader, None, SampleType::INT, fmt.info.bits_per_sample, ByteOrder::LITTLE_ENDIAN).collect();
+
+        Ok((fmt, samples))
+    }
+
+    #[test]
+    fn valid_bits_keep_the_container_size() {
+        let samples = [i32::MAX & !0xFF, i32::MIN, 0x1234_5600, -0x100];
+
+        for (bits_per_sample, valid_bits_per_sample) in [(32, 24), (24, 20)] {
+            let (fmt, decoded) = read_file(&extensible_file(bits_per_sample, valid_bits_per_sample, &samples)).unwrap();
+
+            assert_eq!(fmt.info.bits_per_sample, bits_per_sample);
+            assert_eq!(fmt.info.sample_type, Some(SampleType::INT));
+            assert_eq!(fmt.valid_bits_per_sample, valid_bits_per_sample);
+
+            // Read one container at a time, the valid bits don't change the stride.
+            let bytes_per_sample = bits_per_sample as usize / 8;
+            let expected: Vec<i32> = samples
+                .iter()
+                .map(|s| i32::read(&mut &s.to_le_bytes()[4 - bytes_per_sample..], SampleType::INT, bits_per_sample).unwrap())
+                .collect();
+            assert_eq!(decoded, expected);
+        }
+    }
+
+    #[test]
+    fn valid_bits_bigger_than_the_container() {
+        assert!(read_file(&extensible_file(32, 33, &[0])).is_err());
+    }
+
+    #[test]
+    fn g711_keeps_the_channel_layout() {
+        let info = AudioInfo { channels: 6, channel_layout: Some(ChannelLayout::SURROUND_5_1), ..info(SampleType::ALAW, 8) };
+        let data = fmt_chunk_data(&info, ByteOrder::LITTLE_ENDIAN).unwrap();
+
+        assert_eq!(u16::from_le_bytes(data[..2].try_into().unwrap()), WAVE_FORMAT_EXTENSIBLE);
+        assert_eq!(data[24..40], sub_format_guid(WAVE_FORMAT_ALAW));
+
+        let fmt = WavFmt::read(&data, ByteOrder::LITTLE_ENDIAN).unwrap();
+        assert_eq!(fmt.info.sample_type, Some(SampleType::ALAW));
+        assert_eq!(fmt.info.channel_layout, Some(ChannelLayout::SURROUND_5_1));
+
+        // Stereo with its default layout stays a plain fmt chunk.
+        let info = AudioInfo { channels: 2, channel_layout: None, ..info };
+        let data = fmt_chunk_data(&info, ByteOrder::LITTLE_ENDIAN).unwrap();
+        assert_eq!(u16::from_le_bytes(data[..2].try_into().unwrap()), WAVE_FORMAT_ALAW);
+    }
+
+    #[test]
+    fn ima_adpcm_needs_the_default_layout() {
+        let info = AudioInfo { channel_layout: Some(ChannelLayout::STEREO), ..info(SampleType::IMA_ADPCM, 4) };
+        assert!(fmt_chunk_data(&info, ByteOrder::LITTLE_ENDIAN).is_ok());
+
+        let info = AudioInfo { channel_layout: Some(ChannelLayout::from_mask(0x600)), ..info };
+        assert!(fmt_chunk_data(&info, ByteOrder::LITTLE_ENDIAN).is_err());
+    }
+}
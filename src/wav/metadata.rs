@@ -0,0 +1,237 @@
+use crate::Result;
+use super::{sub_chunks, write_sub_chunk};
+
+// ------------------------- INFO TAGS --------------------------
+
+/// The sub-chunks of a LIST-INFO chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavInfoTag {
+    /// Title.
+    INAM,
+    /// Artist.
+    IART,
+    /// Album, or the product the file is part of.
+    IPRD,
+    /// Track number.
+    ITRK,
+    /// Comment.
+    ICMT,
+    /// Copyright.
+    ICOP,
+    /// Software used to create the file.
+    ISFT,
+    /// Creation date, YYYY-MM-DD.
+    ICRD,
+    /// Genre.
+    IGNR,
+    /// Engineer.
+    IENG,
+    /// Technician.
+    ITCH,
+    /// Keywords.
+    IKEY,
+    /// Subject.
+    ISBJ,
+    /// Source.
+    ISRC,
+    /// Commissioned by.
+    ICMS,
+    OTHER([u8; 4]),
+}
+impl From<[u8; 4]> for WavInfoTag {
+    fn from(value: [u8; 4]) -> Self {
+        match &value {
+            b"INAM" => Self::INAM,
+            b"IART" => Self::IART,
+            b"IPRD" => Self::IPRD,
+            b"ITRK" => Self::ITRK,
+            b"ICMT" => Self::ICMT,
+            b"ICOP" => Self::ICOP,
+            b"ISFT" => Self::ISFT,
+            b"ICRD" => Self::ICRD,
+            b"IGNR" => Self::IGNR,
+            b"IENG" => Self::IENG,
+            b"ITCH" => Self::ITCH,
+            b"IKEY" => Self::IKEY,
+            b"ISBJ" => Self::ISBJ,
+            b"ISRC" => Self::ISRC,
+            b"ICMS" => Self::ICMS,
+            _ => Self::OTHER(value),
+        }
+    }
+}
+impl From<WavInfoTag> for [u8; 4] {
+    fn from(value: WavInfoTag) -> Self {
+        match value {
+            WavInfoTag::INAM => *b"INAM",
+            WavInfoTag::IART => *b"IART",
+            WavInfoTag::IPRD => *b"IPRD",
+            WavInfoTag::ITRK => *b"ITRK",
+            WavInfoTag::ICMT => *b"ICMT",
+            WavInfoTag::ICOP => *b"ICOP",
+            WavInfoTag::ISFT => *b"ISFT",
+            WavInfoTag::ICRD => *b"ICRD",
+            WavInfoTag::IGNR => *b"IGNR",
+            WavInfoTag::IENG => *b"IENG",
+            WavInfoTag::ITCH => *b"ITCH",
+            WavInfoTag::IKEY => *b"IKEY",
+            WavInfoTag::ISBJ => *b"ISBJ",
+            WavInfoTag::ISRC => *b"ISRC",
+            WavInfoTag::ICMS => *b"ICMS",
+            WavInfoTag::OTHER(value) => value,
+        }
+    }
+}
+
+// ------------------------- METADATA --------------------------
+
+/// Text metadata stored in LIST-INFO chunks, in the order it appears in the file.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct WavMetadata {
+    entries: Vec<(WavInfoTag, String)>,
+}
+impl WavMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, tag: WavInfoTag) -> Option<&str> {
+        self.entries.iter()
+            .find(|(entry_tag, _)| *entry_tag == tag)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Replaces the value if the tag is already present.
+    pub fn set(&mut self, tag: WavInfoTag, value: impl Into<String>) {
+        let value = value.into();
+
+        match self.entries.iter_mut().find(|(entry_tag, _)| *entry_tag == tag) {
+            Some((_, entry_value)) => *entry_value = value,
+            None => self.entries.push((tag, value)),
+        }
+    }
+
+    pub fn remove(&mut self, tag: WavInfoTag) -> Option<String> {
+        let index = self.entries.iter().position(|(entry_tag, _)| *entry_tag == tag)?;
+
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Adds the entries of `other`, replacing the ones that are already present.
+    pub fn merge(&mut self, other: WavMetadata) {
+        for (tag, value) in other.entries {
+            self.set(tag, value);
+        }
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (WavInfoTag, &str)> {
+        self.entries.iter().map(|(tag, value)| (*tag, value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Reads the sub-chunks of a LIST-INFO chunk, `data` starts after the "INFO" list type.
+    pub(super) fn read_info(&mut self, data: &[u8]) -> Result<()> {
+        for (id, value) in sub_chunks(data)? {
+            self.set(id.into(), read_zstr(value));
+        }
+
+        Ok(())
+    }
+
+    /// The LIST chunk data, starting with the "INFO" list type.
+    pub(super) fn to_list_data(&self) -> Vec<u8> {
+        let mut data = b"INFO".to_vec();
+        for (tag, value) in &self.entries {
+            let mut text = value.as_bytes().to_vec();
+            // Strings are null terminated.
+            text.push(0);
+
+            write_sub_chunk(&mut data, &(*tag).into(), &text);
+        }
+
+        data
+    }
+}
+
+/// Text in RIFF chunks is null terminated, old files use Latin-1 instead of UTF-8.
+pub(super) fn read_zstr(data: &[u8]) -> String {
+    let end = data.iter().position(|&byte| byte == 0).unwrap_or(data.len());
+    let data = &data[..end];
+
+    match std::str::from_utf8(data) {
+        Ok(text) => text.to_string(),
+        Err(_) => data.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use crate::{decoder::LgDecoder, encoder::LgEncoder, SampleType};
+    use crate::wav::fixtures::{info, samples};
+    use crate::wav::{LgWavDecoder, LgWavEncoder, WavContainer};
+    use super::{read_zstr, WavInfoTag, WavMetadata};
+
+    fn metadata() -> WavMetadata {
+        let mut metadata = WavMetadata::new();
+        metadata.set(WavInfoTag::INAM, "Title");
+        metadata.set(WavInfoTag::IART, "Ünïcödé");
+        // Odd sized with its null, so it is padded.
+        metadata.set(WavInfoTag::OTHER(*b"IXYZ"), "ab");
+
+        metadata
+    }
+
+    /// Writes the metadata before or after the samples.
+    fn encode(metadata: &WavMetadata, before_data: bool) -> Vec<u8> {
+        let mut encoder = LgWavEncoder::from_writer(io::Cursor::new(Vec::new()), info(SampleType::INT, 16), WavContainer::RIFF).unwrap();
+        if before_data {
+            encoder.write_metadata(metadata).unwrap();
+        }
+        encoder.encode_samples(&samples(2000)).unwrap();
+        if !before_data {
+            encoder.write_metadata(metadata).unwrap();
+        }
+
+        encoder.into_inner().unwrap().into_inner()
+    }
+
+    #[test]
+    fn list_info_before_data() {
+        let metadata = metadata();
+        let file = encode(&metadata, true);
+
+        let mut decoder = LgWavDecoder::from_reader(&file[..]).unwrap();
+        assert_eq!(decoder.metadata(), Some(&metadata));
+        assert_eq!(decoder.samples::<i16>().collect::<Vec<_>>(), samples(2000));
+    }
+
+    #[test]
+    fn list_info_after_data() {
+        let metadata = metadata();
+        let file = encode(&metadata, false);
+
+        // Only found when the decoder can seek past the data.
+        let decoder = LgWavDecoder::from_reader(&file[..]).unwrap();
+        assert_eq!(decoder.metadata(), None);
+
+        let mut decoder = LgWavDecoder::from_seekable(io::Cursor::new(file)).unwrap();
+        assert_eq!(decoder.metadata(), Some(&metadata));
+        assert_eq!(decoder.samples::<i16>().collect::<Vec<_>>(), samples(2000));
+    }
+
+    #[test]
+    fn zstr_encodings() {
+        assert_eq!(read_zstr("Café\0junk".as_bytes()), "Café");
+        // Not UTF-8, so every byte is a Latin-1 character.
+        assert_eq!(read_zstr(b"Caf\xE9\0junk"), "Café");
+        assert_eq!(read_zstr(b"no null"), "no null");
+    }
+}
@@ -1,21 +1,39 @@
+use std::io;
 use std::marker::PhantomData;
 use std::fmt::Debug;
-use crate::reader::LgReader;
-use crate::{AudioInfo, Sample, SampleType};
+use crate::error::Error;
+use crate::reader::{BigEndian, LgReader};
+use crate::{AudioInfo, ByteOrder, Result, Sample, SampleType};
+use adpcm::AdpcmBlocks;
+use cue::WavCueText;
 
+pub(crate) mod adpcm;
+pub mod bext;
+pub mod cue;
 pub mod decoder;
 pub mod encoder;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub(crate) mod fmt;
+pub mod metadata;
 pub mod reader;
+pub mod sampler;
 pub mod writer;
 
 pub use decoder::LgWavDecoder;
-pub use encoder::LgWavEncoder;
+pub use encoder::{LgWavEncoder, LgWavStreamEncoder};
+pub use bext::WavBext;
+pub use cue::WavCuePoint;
+pub use metadata::{WavInfoTag, WavMetadata};
+pub use sampler::{WavInstrument, WavLoopType, WavSampleLoop, WavSampler};
 
 // ------------------------- WAVE FORMATS --------------------------
 const WAVE_FORMAT_PCM: u16 =        0x0001;
+const WAVE_FORMAT_ADPCM: u16 =      0x0002;
 const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
 const WAVE_FORMAT_ALAW: u16 =       0x0006;
 const WAVE_FORMAT_MULAW: u16 =      0x0007;
+const WAVE_FORMAT_IMA_ADPCM: u16 =  0x0011;
 const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
 
 #[allow(non_camel_case_types)]
@@ -23,9 +41,12 @@ const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
 pub enum WavFmtTag {
     #[default]
     WAVE_FORMAT_PCM,
+    /// Microsoft ADPCM.
+    WAVE_FORMAT_ADPCM,
     WAVE_FORMAT_IEEE_FLOAT,
     WAVE_FORMAT_ALAW,
     WAVE_FORMAT_MULAW,
+    WAVE_FORMAT_IMA_ADPCM,
     WAVE_FORMAT_EXTENSIBLE,
     OTHER(u16)
 }
@@ -33,37 +54,164 @@ impl From<u16> for WavFmtTag {
     fn from(value: u16) -> Self {
         match value {
             WAVE_FORMAT_PCM =>          Self::WAVE_FORMAT_PCM,
+            WAVE_FORMAT_ADPCM =>        Self::WAVE_FORMAT_ADPCM,
             WAVE_FORMAT_IEEE_FLOAT =>   Self::WAVE_FORMAT_IEEE_FLOAT,
             WAVE_FORMAT_ALAW =>         Self::WAVE_FORMAT_ALAW,
             WAVE_FORMAT_MULAW =>        Self::WAVE_FORMAT_MULAW,
+            WAVE_FORMAT_IMA_ADPCM =>    Self::WAVE_FORMAT_IMA_ADPCM,
             WAVE_FORMAT_EXTENSIBLE =>   Self::WAVE_FORMAT_EXTENSIBLE,
             _ => Self::OTHER(value),
         }
     }
 }
-impl Into<u16> for WavFmtTag {
-    fn into(self) -> u16 {
+impl From<WavFmtTag> for u16 {
+    fn from(value: WavFmtTag) -> Self {
+        match value {
+            WavFmtTag::WAVE_FORMAT_PCM =>        WAVE_FORMAT_PCM,
+            WavFmtTag::WAVE_FORMAT_ADPCM =>      WAVE_FORMAT_ADPCM,
+            WavFmtTag::WAVE_FORMAT_IEEE_FLOAT => WAVE_FORMAT_IEEE_FLOAT,
+            WavFmtTag::WAVE_FORMAT_ALAW =>       WAVE_FORMAT_ALAW,
+            WavFmtTag::WAVE_FORMAT_MULAW =>      WAVE_FORMAT_MULAW,
+            WavFmtTag::WAVE_FORMAT_IMA_ADPCM =>  WAVE_FORMAT_IMA_ADPCM,
+            WavFmtTag::WAVE_FORMAT_EXTENSIBLE => WAVE_FORMAT_EXTENSIBLE,
+            WavFmtTag::OTHER(value) => value,
+        }
+    }
+}
+
+/// The KSDATAFORMAT_SUBTYPE GUIDs used by WAVE_FORMAT_EXTENSIBLE start with the 
+/// fmt_tag they stand for and always end with these bytes.
+const SUB_FORMAT_GUID_SUFFIX: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71
+];
+
+/// Builds the sub_format GUID of WAVE_FORMAT_EXTENSIBLE for a fmt_tag.
+pub(super) fn sub_format_guid(fmt_tag: u16) -> [u8; 16] {
+    let mut guid = [0; 16];
+    guid[..2].copy_from_slice(&fmt_tag.to_le_bytes());
+    guid[2..].copy_from_slice(&SUB_FORMAT_GUID_SUFFIX);
+
+    guid
+}
+
+/// Gets the fmt_tag out of a WAVE_FORMAT_EXTENSIBLE sub_format GUID, if it is one of the known ones.
+pub(super) fn sub_format_tag(guid: &[u8; 16]) -> Option<WavFmtTag> {
+    if guid[2..] != SUB_FORMAT_GUID_SUFFIX {
+        return None;
+    }
+
+    Some(u16::from_le_bytes([guid[0], guid[1]]).into())
+}
+
+/// Formats a GUID the way it is usually written, {00000001-0000-0010-8000-00AA00389B71}.
+pub(super) fn guid_to_string(guid: &[u8; 16]) -> String {
+    std::format!(
+        "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+        u32::from_le_bytes([guid[0], guid[1], guid[2], guid[3]]),
+        u16::from_le_bytes([guid[4], guid[5]]),
+        u16::from_le_bytes([guid[6], guid[7]]),
+        guid[8], guid[9], guid[10], guid[11], guid[12], guid[13], guid[14], guid[15],
+    )
+}
+
+// ------------------------- CONTAINERS --------------------------
+
+/// The header the encoder writes.
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavContainer {
+    /// Plain RIFF WAVE, files can not be bigger than 4 GiB.
+    #[default]
+    RIFF,
+    /// RIFF with a ds64 chunk holding 64 bit sizes, for files bigger than 4 GiB.
+    RF64,
+    /// RIFF with a JUNK chunk that has room for a ds64 chunk. If the file ends up 
+    /// bigger than 4 GiB, it is turned into RF64 when finishing.
+    RF64_AUTO,
+    /// RIFF with big endian sizes and samples, files can not be bigger than 4 GiB. 
+    /// Metadata chunks can not be written to it.
+    RIFX,
+}
+impl WavContainer {
+    pub(crate) fn byte_order(self) -> ByteOrder {
         match self {
-            Self::WAVE_FORMAT_PCM =>        WAVE_FORMAT_PCM,
-            Self::WAVE_FORMAT_IEEE_FLOAT => WAVE_FORMAT_IEEE_FLOAT,
-            Self::WAVE_FORMAT_ALAW =>       WAVE_FORMAT_ALAW,
-            Self::WAVE_FORMAT_MULAW =>      WAVE_FORMAT_MULAW,
-            Self::WAVE_FORMAT_EXTENSIBLE => WAVE_FORMAT_EXTENSIBLE,
-            Self::OTHER(value) => value,
+            WavContainer::RIFX => ByteOrder::BIG_ENDIAN,
+            _ => ByteOrder::LITTLE_ENDIAN,
         }
     }
 }
 
 // ------------------------- CHUNKS --------------------------
+#[allow(clippy::upper_case_acronyms)]
 pub(super) enum WavChunks {
-    FMT(AudioInfo),
-    /// Not used.
-    FACT,
+    FMT(WavFmt),
+    /// Number of sample frames (dwSampleLength).
+    FACT(u64),
     /// Chunk size
-    DATA(u32),
+    DATA(u64),
+    /// LIST-INFO.
+    INFO(WavMetadata),
+    BEXT(Box<WavBext>),
+    CUE(Vec<WavCuePoint>),
+    SMPL(WavSampler),
+    INST(WavInstrument),
+    /// LIST-adtl, the texts of the cue points by their id.
+    ADTL(Vec<(u32, WavCueText)>),
+    /// Any chunk the decoder does not use, it was skipped.
+    UNKNOWN(WavChunkInfo),
 }
 
+/// A chunk that was skipped while looking for the audio data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavChunkInfo {
+    pub id: [u8; 4],
+    /// Size of the chunk data, without the padding byte of odd sized chunks.
+    pub size: u32,
+    /// Where the chunk data starts, from the start of the file.
+    pub position: u64,
+}
+
+/// The parts of the fmt chunk that do not fit in [`AudioInfo`].
+#[derive(Debug, Clone)]
+pub(super) struct WavFmt {
+    pub(super) info: AudioInfo,
+    pub(super) block_align: u16,
+    /// Bits of every sample that hold audio, `info.bits_per_sample` is the container size the samples are read with.
+    /// Only WAVE_FORMAT_EXTENSIBLE can make it smaller.
+    pub(super) valid_bits_per_sample: u16,
+    /// Only used by ADPCM formats.
+    pub(super) samples_per_block: u16,
+    /// Only used by MS_ADPCM.
+    pub(super) coefficients: Vec<(i16, i16)>,
+}
+/// Splits chunk data made of sub-chunks (like LIST) into (id, data) pairs.
+/// Sub-chunks that claim to be bigger than what is left are cut short.
+pub(super) fn sub_chunks(mut data: &[u8]) -> Result<Vec<([u8; 4], &[u8])>> {
+    let mut result = Vec::new();
+
+    while data.len() >= 8 {
+        let id: [u8; 4] = data.read_next_bytes()?;
+        let size = (data.read_le_u32()? as usize).min(data.len());
+
+        result.push((id, &data[..size]));
+        
+        // Sub-chunks are padded just like chunks.
+        data = &data[(size + (size & 1)).min(data.len())..];
+    }
+
+    Ok(result)
+}
+
+/// Appends a sub-chunk, with the padding byte if needed.
+pub(super) fn write_sub_chunk(buffer: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+    buffer.extend_from_slice(id);
+    buffer.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(data);
 
+    if data.len() % 2 == 1 {
+        buffer.push(0);
+    }
+}
 
 // ------------------------- SAMPLE --------------------------
 
@@ -72,17 +220,28 @@ where R: LgReader,
 {
     bits_per_sample: u16,
     sample_type: SampleType,
+    byte_order: ByteOrder,
     reader: &'si mut R,
+    /// Only present for block based formats (ADPCM).
+    blocks: Option<&'si mut AdpcmBlocks>,
     _phantom: PhantomData<S>,
 }
 impl<'si, R, S: Sample> LgWavSampleIter<'si, R, S> 
 where R: LgReader,
 {
-    fn new(reader: &'si mut R, sample_type: SampleType, bits_per_sample: u16) -> Self {
+    pub(crate) fn new(
+        reader: &'si mut R, 
+        blocks: Option<&'si mut AdpcmBlocks>, 
+        sample_type: SampleType, 
+        bits_per_sample: u16,
+        byte_order: ByteOrder,
+    ) -> Self {
         Self {
             sample_type,
             bits_per_sample,
+            byte_order,
             reader,
+            blocks,
             _phantom: PhantomData,
         }
     }
@@ -93,6 +252,460 @@ where R: LgReader,
     type Item = S;
 
     fn next(&mut self) -> Option<Self::Item> {
-        S::read(self.reader, self.sample_type, self.bits_per_sample).ok()
+        match &mut self.blocks {
+            Some(blocks) => blocks.next_sample(self.reader).ok().flatten(),
+            None => match self.byte_order {
+                ByteOrder::LITTLE_ENDIAN => S::read(self.reader, self.sample_type, self.bits_per_sample).ok(),
+                ByteOrder::BIG_ENDIAN => S::read(&mut BigEndian(&mut *self.reader), self.sample_type, self.bits_per_sample).ok(),
+            },
+        }
+    }
+}
+// ------------------------- BULK --------------------------
+
+/// Samples converted at a time by [`LgWavBulkReader`].
+const READ_SAMPLES_PART: usize = 16384;
+
+/// A reader of the audio data that can stop at its end without failing.
+pub(crate) trait LgDataReader: LgReader {
+    /// Reads until `buffer` is full or the data ends, returns how many bytes were read.
+    fn read_up_to(&mut self, buffer: &mut [u8]) -> Result<usize>;
+}
+impl<R: io::Read> LgDataReader for io::Take<R> {
+    fn read_up_to(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let mut read = 0;
+
+        while read < buffer.len() {
+            match io::Read::read(self, &mut buffer[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(Error::Io(e)),
+            }
+        }
+
+        Ok(read)
+    }
+}
+
+/// Reads and converts many samples at once, used by [`crate::decoder::LgDecoder::read_samples`] 
+/// of the WAV and Wave64 decoders.
+#[derive(Debug)]
+pub(crate) struct LgWavBulkReader {
+    sample_type: SampleType,
+    bits_per_sample: u16,
+    byte_order: ByteOrder,
+    /// Raw samples of the last read, in little endian. Kept to avoid allocating every call.
+    bytes: Vec<u8>,
+    /// The start of a sample the data had no room for, it goes first on the next read.
+    partial: Vec<u8>,
+    /// The raw samples of one channel, to convert planar buffers in bulk.
+    channel_bytes: Vec<u8>,
+}
+impl LgWavBulkReader {
+    pub(crate) fn new(info: &AudioInfo, byte_order: ByteOrder) -> Self {
+        Self {
+            sample_type: info.sample_type.unwrap_or(SampleType::INT),
+            bits_per_sample: info.bits_per_sample,
+            byte_order,
+            bytes: Vec::new(),
+            partial: Vec::new(),
+            channel_bytes: Vec::new(),
+        }
+    }
+
+    /// Forgets the start of a sample that was kept, the reader moved somewhere else.
+    pub(crate) fn clear(&mut self) {
+        self.partial.clear();
+    }
+
+    /// Fills `buffer` with the next samples, returns how many were read.
+    pub(crate) fn read_samples<S: Sample>(
+        &mut self, 
+        reader: &mut impl LgDataReader, 
+        mut blocks: Option<&mut AdpcmBlocks>, 
+        buffer: &mut [S]
+    ) -> Result<usize> {
+        let (sample_type, bits_per_sample) = self.format(blocks.is_some());
+        let mut len = 0;
+
+        // Big buffers are converted in parts, so the raw samples don't take as much memory as them.
+        for part in buffer.chunks_mut(READ_SAMPLES_PART) {
+            let part_len = self.read_raw(reader, blocks.as_deref_mut(), part.len())?;
+            S::read_slice(&self.bytes, sample_type, bits_per_sample, &mut part[..part_len])?;
+
+            len += part_len;
+            if part_len < part.len() {
+                break;
+            }
+        }
+
+        Ok(len)
+    }
+
+    /// Fills one buffer per channel, see [`crate::decoder::LgDecoder::decode_planar`].
+    pub(crate) fn decode_planar<S: Sample, C: AsMut<[S]>>(
+        &mut self, 
+        reader: &mut impl LgDataReader, 
+        mut blocks: Option<&mut AdpcmBlocks>, 
+        buffers: &mut [C]
+    ) -> Result<usize> {
+        let (sample_type, bits_per_sample) = self.format(blocks.is_some());
+        let bytes_per_sample = bits_per_sample as usize / 8;
+        let channels = buffers.len();
+        let len = buffers.iter_mut().map(|buffer| buffer.as_mut().len()).min().unwrap_or(0);
+        let mut frames = 0;
+
+        while frames < len {
+            let part = (READ_SAMPLES_PART / channels).clamp(1, len - frames) * channels;
+            let part_len = self.read_raw(reader, blocks.as_deref_mut(), part)?;
+
+            for (channel, buffer) in buffers.iter_mut().enumerate() {
+                let bytes = self.channel_bytes(channel, channels, bytes_per_sample);
+                let channel_len = bytes.len() / bytes_per_sample;
+                S::read_slice(bytes, sample_type, bits_per_sample, &mut buffer.as_mut()[frames..frames + channel_len])?;
+            }
+
+            // A partial last frame is written but not counted.
+            frames += part_len / channels;
+            if part_len < part {
+                break;
+            }
+        }
+
+        Ok(frames)
+    }
+
+    /// The rest of the samples, see [`crate::decoder::LgDecoder::planar_samples`].
+    pub(crate) fn planar_samples<S: Sample>(
+        &mut self, 
+        reader: &mut impl LgDataReader, 
+        mut blocks: Option<&mut AdpcmBlocks>, 
+        channels: usize
+    ) -> Vec<Vec<S>> {
+        let (sample_type, bits_per_sample) = self.format(blocks.is_some());
+        let bytes_per_sample = bits_per_sample as usize / 8;
+        let channels = channels.max(1);
+        let part = (READ_SAMPLES_PART / channels).max(1) * channels;
+        let mut result: Vec<Vec<S>> = (0..channels).map(|_| Vec::new()).collect();
+        let mut len = 0;
+
+        // Like the sample iterator, an error just ends the samples.
+        while let Ok(part_len @ 1..) = self.read_raw(reader, blocks.as_deref_mut(), part) {
+            for (i, mut bytes) in self.bytes.chunks_exact(bytes_per_sample).enumerate() {
+                match S::read(&mut bytes, sample_type, bits_per_sample) {
+                    Ok(sample) => result[(len + i) % channels].push(sample),
+                    Err(_) => return result,
+                }
+            }
+
+            len += part_len;
+            if part_len < part {
+                break;
+            }
+        }
+
+        result
+    }
+
+    /// How the raw samples are converted, ADPCM is handed out as 16 bit PCM.
+    fn format(&self, adpcm: bool) -> (SampleType, u16) {
+        if adpcm {
+            (SampleType::INT, 16)
+        } else {
+            (self.sample_type, self.bits_per_sample)
+        }
+    }
+
+    /// Gathers the samples of `channel` out of the interleaved `bytes`.
+    fn channel_bytes(&mut self, channel: usize, channels: usize, bytes_per_sample: usize) -> &[u8] {
+        self.channel_bytes.clear();
+        for sample in self.bytes.chunks_exact(bytes_per_sample).skip(channel).step_by(channels) {
+            self.channel_bytes.extend_from_slice(sample);
+        }
+
+        &self.channel_bytes
+    }
+
+    /// Reads up to `samples` whole samples into `bytes` as little endian, returns how many were read.
+    fn read_raw(&mut self, reader: &mut impl LgDataReader, blocks: Option<&mut AdpcmBlocks>, samples: usize) -> Result<usize> {
+        self.bytes.clear();
+        if let Some(blocks) = blocks {
+            return blocks.read_samples(reader, samples, &mut self.bytes);
+        }
+
+        let bytes_per_sample = self.bits_per_sample as usize / 8;
+        let kept = self.partial.len();
+        self.bytes.append(&mut self.partial);
+        self.bytes.resize(samples.max(1) * bytes_per_sample, 0);
+
+        let read = kept + reader.read_up_to(&mut self.bytes[kept..])?;
+        let len = read / bytes_per_sample;
+        self.partial.extend_from_slice(&self.bytes[len * bytes_per_sample..read]);
+        self.bytes.truncate(len * bytes_per_sample);
+
+        if self.byte_order == ByteOrder::BIG_ENDIAN {
+            self.bytes.chunks_exact_mut(bytes_per_sample).for_each(|sample| sample.reverse());
+        }
+
+        Ok(len)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use crate::{decoder::{LgDecoder, LgFrame}, encoder::LgEncoder, tools, AudioInfo, ByteOrder, SampleType};
+    use super::fixtures::{decode, encode, info, samples};
+    use super::{LgWavBulkReader, LgWavDecoder, LgWavEncoder, LgWavStreamEncoder, WavChunkInfo, WavContainer, WAVE_FORMAT_ALAW};
+
+    /// Says the data ended once, after `pause` bytes, like a file that is still being written.
+    struct Paused {
+        data: Vec<u8>,
+        position: usize,
+        pause: Option<usize>,
+    }
+    impl io::Read for Paused {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let end = match self.pause {
+                Some(pause) if self.position >= pause => {
+                    self.pause = None;
+                    return Ok(0);
+                },
+                Some(pause) => pause,
+                None => self.data.len(),
+            };
+
+            let len = buf.len().min(end - self.position);
+            buf[..len].copy_from_slice(&self.data[self.position..self.position + len]);
+            self.position += len;
+
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn read_samples_matches_samples() {
+        let formats = [
+            (SampleType::INT, 8, WavContainer::RIFF),
+            (SampleType::INT, 24, WavContainer::RIFF),
+            (SampleType::INT, 24, WavContainer::RIFX),
+            (SampleType::FLOAT, 64, WavContainer::RIFX),
+            (SampleType::MULAW, 8, WavContainer::RIFF),
+            (SampleType::IMA_ADPCM, 4, WavContainer::RIFF),
+        ];
+
+        for (sample_type, bits_per_sample, container) in formats {
+            // More than one part of the bulk reader.
+            let file = encode(info(sample_type, bits_per_sample), container, &samples(40002));
+            let mut decoder = LgWavDecoder::from_seekable(io::Cursor::new(file)).unwrap();
+            let expected: Vec<i32> = decoder.samples().collect();
+
+            for buffer_len in [7, 40000] {
+                decoder.rewind().unwrap();
+                let mut decoded = Vec::new();
+                let mut buffer = vec![0i32; buffer_len];
+
+                loop {
+                    let len = decoder.read_samples(&mut buffer).unwrap();
+                    if len == 0 {
+                        break;
+                    }
+                    decoded.extend_from_slice(&buffer[..len]);
+                }
+
+                assert_eq!(decoded, expected, "{sample_type:?} {bits_per_sample} {container:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn read_samples_keeps_partial_samples() {
+        let samples = samples(1000);
+        let mut file = Vec::new();
+        let mut encoder = LgWavStreamEncoder::new(&mut file, info(SampleType::INT, 24)).unwrap();
+        encoder.encode_samples(&samples).unwrap();
+        encoder.finish().unwrap();
+
+        // The data seems to end one byte into the sample 500.
+        let header = file.len() - samples.len() * 3;
+        let reader = Paused { data: file, position: 0, pause: Some(header + 500 * 3 + 1) };
+        let mut decoder = LgWavDecoder::from_reader(reader).unwrap();
+
+        let mut buffer = vec![0i32; 2000];
+        assert_eq!(decoder.read_samples(&mut buffer).unwrap(), 500);
+        assert_eq!(decoder.read_samples(&mut buffer[500..]).unwrap(), 500);
+        assert_eq!(buffer[..1000], samples.iter().map(|&s| (s as i32) << 16).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn alaw_round_trip() {
+        let samples = samples(2000);
+        let file = encode(info(SampleType::ALAW, 8), WavContainer::RIFF, &samples);
+
+        // fmt_tag.
+        assert_eq!(u16::from_le_bytes(file[20..22].try_into().unwrap()), WAVE_FORMAT_ALAW);
+
+        let expected: Vec<i16> = samples.iter().map(|&s| tools::decode_alaw(tools::encode_alaw(s))).collect();
+        assert_eq!(decode(file), expected);
+    }
+
+    #[test]
+    fn odd_unknown_chunks_are_skipped_with_their_padding() {
+        let samples = samples(2000);
+        let mut file = encode(info(SampleType::INT, 16), WavContainer::RIFF, &samples);
+
+        // A 3 byte chunk and its padding byte go right before the data chunk.
+        let data_position = file.windows(4).position(|id| id == b"data").unwrap();
+        file.splice(data_position..data_position, *b"abcd\x03\0\0\0xyz\0");
+        let riff_size = file.len() as u32 - 8;
+        file[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+        let expected = WavChunkInfo { id: *b"abcd", size: 3, position: data_position as u64 + 8 };
+
+        let mut decoder = LgWavDecoder::from_reader(&file[..]).unwrap();
+        assert_eq!(decoder.skipped_chunks(), [expected]);
+        assert_eq!(decoder.samples::<i16>().collect::<Vec<_>>(), samples);
+
+        let mut decoder = LgWavDecoder::from_seekable(io::Cursor::new(file)).unwrap();
+        assert_eq!(decoder.skipped_chunks(), [expected]);
+        assert_eq!(decoder.samples::<i16>().collect::<Vec<_>>(), samples);
+    }
+
+    #[test]
+    fn pcm_seek_frame() {
+        // Stereo frames of 4, 6 and 8 bytes.
+        for (sample_type, bits_per_sample) in [(SampleType::INT, 16), (SampleType::INT, 24), (SampleType::FLOAT, 32)] {
+            let file = encode(info(sample_type, bits_per_sample), WavContainer::RIFF, &samples(2000));
+            let mut decoder = LgWavDecoder::from_seekable(io::Cursor::new(file)).unwrap();
+            let expected: Vec<i32> = decoder.samples().collect();
+
+            decoder.seek_frame(300).unwrap();
+            let mut buffer = [0i32; 20];
+            assert_eq!(decoder.read_samples(&mut buffer).unwrap(), 20);
+            assert_eq!(buffer[..], expected[600..620]);
+            // The iterator goes on from there too, starting with the first channel.
+            assert_eq!(decoder.samples::<i32>().collect::<Vec<_>>(), expected[620..]);
+
+            decoder.rewind().unwrap();
+            assert_eq!(decoder.samples::<i32>().collect::<Vec<_>>(), expected);
+
+            decoder.seek_frame(1000).unwrap();
+            assert_eq!(decoder.samples::<i32>().count(), 0);
+            decoder.seek_frame(usize::MAX).unwrap();
+            assert_eq!(decoder.read_samples(&mut buffer).unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn pcm_seek_time() {
+        let samples = samples(2000);
+        let file = encode(info(SampleType::INT, 16), WavContainer::RIFF, &samples);
+        let mut decoder = LgWavDecoder::from_seekable(io::Cursor::new(file)).unwrap();
+
+        // 8000 Hz, so 50 ms is frame 400.
+        decoder.seek_time(std::time::Duration::from_millis(50)).unwrap();
+        assert_eq!(decoder.samples::<i16>().collect::<Vec<_>>(), samples[800..]);
+
+        // Rounded down to the frame it falls in.
+        decoder.seek_time(std::time::Duration::from_micros(100)).unwrap();
+        assert_eq!(decoder.samples::<i16>().collect::<Vec<_>>(), samples);
+
+        decoder.seek_time(std::time::Duration::from_secs(1)).unwrap();
+        assert_eq!(decoder.samples::<i16>().count(), 0);
+    }
+
+    #[test]
+    fn frames_end_with_a_partial_frame() {
+        // The last frame only has its first channel.
+        let samples = samples(2001);
+        let file = encode(info(SampleType::INT, 16), WavContainer::RIFF, &samples);
+        let mut decoder = LgWavDecoder::from_seekable(io::Cursor::new(file)).unwrap();
+
+        let frames: Vec<LgFrame<i16>> = decoder.frames().collect();
+        assert_eq!(frames.len(), 1001);
+        assert!(frames[..1000].iter().all(|frame| !frame.is_partial() && frame.samples().len() == 2));
+        assert_eq!(frames[1000], LgFrame::PARTIAL(vec![samples[2000]]));
+        assert_eq!(frames.into_iter().flat_map(LgFrame::into_samples).collect::<Vec<_>>(), samples);
+
+        decoder.rewind().unwrap();
+        let mut frame = [0i16; 2];
+        for i in 0..1000 {
+            assert_eq!(decoder.read_frame(&mut frame).unwrap(), 2);
+            assert_eq!(frame[..], samples[i * 2..i * 2 + 2]);
+        }
+        assert_eq!(decoder.read_frame(&mut frame).unwrap(), 1);
+        assert_eq!(frame[0], samples[2000]);
+        assert_eq!(decoder.read_frame(&mut frame).unwrap(), 0);
+        assert!(decoder.frames::<i16>().next().is_none());
+
+        assert!(decoder.read_frame(&mut [0i16; 1]).is_err());
+        assert!(decoder.read_frame(&mut [0i16; 3]).is_err());
+    }
+
+    #[test]
+    fn encode_frame_needs_every_channel() {
+        let mut encoder = LgWavEncoder::from_writer(io::Cursor::new(Vec::new()), info(SampleType::INT, 16), WavContainer::RIFF).unwrap();
+        encoder.encode_frame(&[1i16, 2]).unwrap();
+        assert!(encoder.encode_frame(&[3i16]).is_err());
+        assert!(encoder.encode_frame(&[3i16, 4, 5]).is_err());
+        // Nothing of the wrong frames was encoded.
+        assert_eq!(encoder.encoded_samples(), 2);
+        encoder.encode_frame(&[3i16, 4]).unwrap();
+
+        assert_eq!(decode(encoder.into_inner().unwrap().into_inner()), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn decode_planar_partial_last_frame() {
+        let info = info(SampleType::INT, 16);
+        let data: Vec<u8> = [1i16, 2, 3].iter().flat_map(|sample| sample.to_le_bytes()).collect();
+        let mut reader = io::Read::take(&data[..], data.len() as u64);
+        let mut buffers = [[0i16; 4]; 2];
+
+        let mut bulk = LgWavBulkReader::new(&info, ByteOrder::LITTLE_ENDIAN);
+        assert_eq!(bulk.decode_planar(&mut reader, None, &mut buffers).unwrap(), 1);
+        assert_eq!(buffers, [[1, 3, 0, 0], [2, 0, 0, 0]]);
+    }
+
+    #[test]
+    fn decode_planar_in_parts() {
+        // Parts of READ_SAMPLES_PART / 3 frames, and a partial last frame.
+        let info = AudioInfo { channels: 3, ..info(SampleType::INT, 24) };
+        let samples = samples(18004);
+        let file = encode(info, WavContainer::RIFF, &samples);
+        let mut decoder = LgWavDecoder::from_seekable(io::Cursor::new(file)).unwrap();
+
+        let mut buffers = vec![vec![0i16; 6010]; 3];
+        assert_eq!(decoder.decode_planar(&mut buffers).unwrap(), 6001);
+        for (channel, buffer) in buffers.iter().enumerate() {
+            let expected: Vec<i16> = samples.iter().copied().skip(channel).step_by(3).collect();
+            assert_eq!(buffer[..expected.len()], expected);
+        }
+
+        // Shorter buffers stop it early, and the next call goes on from there.
+        decoder.rewind().unwrap();
+        let mut buffers = vec![vec![0i16; 6000]; 3];
+        assert_eq!(decoder.decode_planar(&mut buffers).unwrap(), 6000);
+        assert_eq!(decoder.decode_planar(&mut buffers).unwrap(), 1);
+        assert_eq!(buffers[0][..2], [samples[18000], samples[18003]]);
+
+        assert!(decoder.decode_planar(&mut [[0i16; 4]; 2]).is_err());
+    }
+
+    #[test]
+    fn encode_planar_in_parts() {
+        // More frames than ENCODE_PLANAR_PART.
+        let left = samples(5000);
+        let right: Vec<i16> = left.iter().map(|sample| sample.wrapping_neg()).collect();
+
+        let mut encoder = LgWavEncoder::from_writer(io::Cursor::new(Vec::new()), info(SampleType::INT, 16), WavContainer::RIFF).unwrap();
+        encoder.encode_planar(&[&left, &right]).unwrap();
+        assert!(encoder.encode_planar(&[&left[..10], &right[..9]]).is_err());
+        assert!(encoder.encode_planar(&[&left]).is_err());
+        assert_eq!(encoder.encoded_samples(), 10000);
+
+        let expected: Vec<i16> = left.iter().zip(&right).flat_map(|(&l, &r)| [l, r]).collect();
+        assert_eq!(decode(encoder.into_inner().unwrap().into_inner()), expected);
+    }
+}
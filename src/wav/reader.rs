@@ -1,17 +1,42 @@
-use std::{io, usize};
+use std::io;
 use crate::error::Error;
 use crate::reader::LgReader;
-use crate::wav::WavFmtTag;
-use crate::AudioInfo;
-use crate::Result;
-use crate::SampleType;
+use crate::wav::{cue, LgDataReader, WavBext, WavChunkInfo, WavFmt, WavInstrument, WavMetadata, WavSampler};
+use crate::{ByteOrder, Result};
 
 use super::WavChunks;
 
+/// "RIFF", ck_size and "WAVE".
+const HEADER_SIZE: usize = 12;
+/// RF64 files put this in the 32 bit sizes that are stored in the ds64 chunk instead.
+/// Streamed files that don't know their length use it too, but have no ds64 chunk.
+const RF64_SIZE: u32 = 0xFFFFFFFF;
+/// The data chunk of a streamed file that didn't know its length, it goes until the end of the file.
+pub(super) const UNKNOWN_DATA_SIZE: u64 = u64::MAX;
+
+/// The 64 bit sizes of a RF64 file.
+#[derive(Debug, Clone, Copy)]
+struct Ds64 {
+    data_size: u64,
+    /// Number of sample frames, in place of the fact chunk.
+    sample_count: u64,
+}
+
 pub struct LgWavReader<R: io::Read> {
     pub(super) reader: R,
     max_size: usize,
     cursor: usize,
+    /// Where the reader is, from the start of the file.
+    position: u64,
+    /// Where the RIFF chunk ends, from the start of the file.
+    riff_end: u64,
+    /// Where the samples start, from the start of the file.
+    data_start: u64,
+    data_len: usize,
+    /// Only present for RF64 and BW64 files.
+    ds64: Option<Ds64>,
+    /// BIG_ENDIAN for RIFX files.
+    pub(super) byte_order: ByteOrder,
 }
 impl<R: io::Read> LgReader for LgWavReader<R> {
     fn read_into(&mut self, buffer: &mut [u8]) -> Result<()> {
@@ -50,6 +75,12 @@ impl<R: io::Read> LgReader for LgWavReader<R> {
         self.reader.read_le_u32()
     }
 
+    fn read_le_u64(&mut self) -> Result<u64> {
+        self.move_cursor(8)?;
+        
+        self.reader.read_le_u64()
+    }
+
     fn read_le_i8(&mut self) -> Result<i8> {
         self.move_cursor(1)?;
 
@@ -85,6 +116,54 @@ impl<R: io::Read> LgReader for LgWavReader<R> {
         
         self.reader.read_le_f64()
     }
+
+    fn read_be_u16(&mut self) -> Result<u16> {
+        self.move_cursor(2)?;
+
+        self.reader.read_be_u16()
+    }
+
+    fn read_be_u32(&mut self) -> Result<u32> {
+        self.move_cursor(4)?;
+
+        self.reader.read_be_u32()
+    }
+
+    fn read_be_u64(&mut self) -> Result<u64> {
+        self.move_cursor(8)?;
+
+        self.reader.read_be_u64()
+    }
+
+    fn read_be_i16(&mut self) -> Result<i16> {
+        self.move_cursor(2)?;
+
+        self.reader.read_be_i16()
+    }
+
+    fn read_be_i32(&mut self) -> Result<i32> {
+        self.move_cursor(4)?;
+
+        self.reader.read_be_i32()
+    }
+
+    fn read_be_i32_24(&mut self) -> Result<i32> {
+        self.move_cursor(3)?;
+
+        self.reader.read_be_i32_24()
+    }
+
+    fn read_be_f32(&mut self) -> Result<f32> {
+        self.move_cursor(4)?;
+
+        self.reader.read_be_f32()
+    }
+
+    fn read_be_f64(&mut self) -> Result<f64> {
+        self.move_cursor(8)?;
+
+        self.reader.read_be_f64()
+    }
 }
 impl<R: io::Read> LgWavReader<R> {
     pub(super) fn new(reader: R) -> Result<Self> {
@@ -92,190 +171,295 @@ impl<R: io::Read> LgWavReader<R> {
     }
 
     pub(super) fn read_header(mut reader: R) -> Result<Self> {
-        if b"RIFF" != &reader.read_next_bytes()? {
-            return Err(Error::WrongHeader);
-        }
+        let ck_id: [u8; 4] = reader.read_next_bytes()?;
+        // BW64 is the same as RF64, RIFX is RIFF with big endian values.
+        let (is_rf64, byte_order) = match &ck_id {
+            b"RIFF" => (false, ByteOrder::LITTLE_ENDIAN),
+            b"RIFX" => (false, ByteOrder::BIG_ENDIAN),
+            b"RF64" | b"BW64" => (true, ByteOrder::LITTLE_ENDIAN),
+            _ => return Err(Error::WrongHeader),
+        };
         
-        let ck_size = reader.read_le_u32()? - 4;
+        let ck_size = match byte_order {
+            ByteOrder::LITTLE_ENDIAN => reader.read_le_u32()?,
+            ByteOrder::BIG_ENDIAN => reader.read_be_u32()?,
+        };
         
         if b"WAVE" != &reader.read_next_bytes()? {
             return Err(Error::WrongHeader);
         }
         
-        Ok(Self {
+        let mut result = Self {
             reader,
-            max_size: ck_size as usize,
+            max_size: 0,
             cursor: 0,
-        })
+            position: HEADER_SIZE as u64,
+            riff_end: 0,
+            data_start: 0,
+            data_len: 0,
+            ds64: None,
+            byte_order,
+        };
+
+        // The real RIFF ck_size of RF64 files is in the ds64 chunk, that must be the first one.
+        let ck_size = if is_rf64 {
+            result.max_size = usize::MAX;
+            result.read_ds64_chunk()?
+        }
+        else {
+            ck_size as u64
+        };
+
+        // Everything after "WAVE".
+        let riff_end = 8 + ck_size;
+        if riff_end < result.position {
+            return Err(Error::WrongHeader);
+        }
+        result.riff_end = riff_end;
+        result.max_size = (riff_end - result.position) as usize;
+
+        Ok(result)
+    }
+
+    /// Returns the RIFF ck_size.
+    fn read_ds64_chunk(&mut self) -> Result<u64> {
+        if b"ds64" != &self.read_next_bytes()? {
+            return Err(Error::WrongHeader);
+        }
+
+        let ck_size = self.read_le_u32()? as usize;
+        if ck_size < 24 {
+            return Err(Error::WrongFmtInfo("ds64 chunk must have ck_size of at least 24!".to_string()));
+        }
+
+        let riff_size = self.read_le_u64()?;
+        let data_size = self.read_le_u64()?;
+        let sample_count = self.read_le_u64()?;
+        // The table with the sizes of other big chunks is not needed, only data can be that big.
+        self.skip_bytes(padded(ck_size) - 24)?;
+        
+        self.ds64 = Some(Ds64 {
+            data_size,
+            sample_count,
+        });
+
+        Ok(riff_size)
     }
 
     pub(super) fn read_next_chunk(&mut self) -> Result<WavChunks> {
-        Ok(match &self.read_next_bytes()? {
-            b"fmt " => WavChunks::FMT(self.read_fmt_chunk()?),
-            b"fact" => {
-                self.read_fact_chunk()?;
-                WavChunks::FACT
+        let ck_id = self.read_next_bytes()?;
+
+        // The metadata chunks are only read from little endian files.
+        if self.byte_order == ByteOrder::BIG_ENDIAN && !matches!(&ck_id, b"fmt " | b"fact" | b"data") {
+            return Ok(WavChunks::UNKNOWN(self.skip_chunk(ck_id)?));
+        }
+
+        Ok(match &ck_id {
+            b"fmt " => {
+                let ck_size = self.read_u32()? as usize;
+                let data = self.read_chunk_data(ck_size)?;
+
+                WavChunks::FMT(WavFmt::read(&data, self.byte_order)?)
             },
+            b"fact" => WavChunks::FACT(self.read_fact_chunk()?),
             b"data" => {
                 // Some files will have metadata in them after the data chunk.
                 // We don't want that to be marked as a sample, so we make sure we only read the rest of the data.
-                let data_ck_size = self.read_le_u32()?;
+                let data_ck_size = match (self.read_u32()?, self.ds64) {
+                    (RF64_SIZE, Some(ds64)) => ds64.data_size,
+                    (RF64_SIZE, None) => UNKNOWN_DATA_SIZE,
+                    (ck_size, _) => ck_size as u64,
+                };
                 self.max_size = data_ck_size as usize;
                 self.cursor = 0;
+                self.data_start = self.position;
+                self.data_len = data_ck_size as usize;
 
                 WavChunks::DATA(data_ck_size)
             },
 
-            _ => return Err(Error::WrongFmtInfo("Currently do not support more chunks other than fmt and data!".to_string())),
+            b"LIST" => self.read_list_chunk()?,
+            b"cue " => {
+                let ck_size = self.read_le_u32()? as usize;
+                let data = self.read_chunk_data(ck_size)?;
+
+                WavChunks::CUE(cue::read_cue(&data)?)
+            },
+            b"bext" => {
+                let ck_size = self.read_le_u32()? as usize;
+                let data = self.read_chunk_data(ck_size)?;
+
+                WavChunks::BEXT(Box::new(WavBext::read(&data)?))
+            },
+            b"smpl" => {
+                let ck_size = self.read_le_u32()? as usize;
+                let data = self.read_chunk_data(ck_size)?;
+
+                WavChunks::SMPL(WavSampler::read(&data)?)
+            },
+            b"inst" => {
+                let ck_size = self.read_le_u32()? as usize;
+                let data = self.read_chunk_data(ck_size)?;
+
+                WavChunks::INST(WavInstrument::read(&data)?)
+            },
+            _ => WavChunks::UNKNOWN(self.skip_chunk(ck_id)?),
         })
     }
-    
-    pub(super) fn read_fmt_chunk(&mut self) -> Result<AudioInfo> {
-        let ck_size = self.read_le_u32()? as usize;
 
-        if ck_size > 40 || ck_size < 16 { return Err(Error::WrongFmt); }
-
-        let fmt_tag: WavFmtTag = self.read_le_u16()?.into();
-        let channels = self.read_le_u16()?;
-        let samples_per_sec = self.read_le_u32()?;
-        let _avg_bytes_per_sec = self.read_le_u32()?;
-        let _block_align = self.read_le_u16()?;
-        let bits_per_sample = self.read_le_u16()?;
-
-        let mut info = AudioInfo {
-            channels,
-            sample_rate: samples_per_sec,
-            bits_per_sample,
-            sample_type: Some(match fmt_tag {
-                WavFmtTag::WAVE_FORMAT_PCM 
-                | WavFmtTag::WAVE_FORMAT_ALAW 
-                | WavFmtTag::WAVE_FORMAT_MULAW 
-                | WavFmtTag::WAVE_FORMAT_EXTENSIBLE
-                | WavFmtTag::OTHER(_) => SampleType::INT,
-                WavFmtTag::WAVE_FORMAT_IEEE_FLOAT => SampleType::FLOAT
-            })
-        };
+    fn read_list_chunk(&mut self) -> Result<WavChunks> {
+        let ck_size = self.read_le_u32()?;
+        let position = self.position;
+        let data = self.read_chunk_data(ck_size as usize)?;
+
+        Ok(match data.get(..4) {
+            Some(b"INFO") => {
+                let mut metadata = WavMetadata::new();
+                metadata.read_info(&data[4..])?;
+
+                WavChunks::INFO(metadata)
+            },
+            Some(b"adtl") => WavChunks::ADTL(cue::read_adtl(&data[4..])?),
+
+            _ => WavChunks::UNKNOWN(WavChunkInfo {
+                id: *b"LIST",
+                size: ck_size,
+                position,
+            }),
+        })
+    }
 
-        // Time to check if the info is ok.
-        check_fmt(&info)?;
+    /// Reads the whole chunk data, and skips the padding byte.
+    fn read_chunk_data(&mut self, ck_size: usize) -> Result<Vec<u8>> {
+        let mut data = vec![0; ck_size];
+        self.read_into(&mut data)?;
+        self.skip_bytes(padded(ck_size) - ck_size)?;
 
-        match (fmt_tag, ck_size) {
-            (WavFmtTag::WAVE_FORMAT_PCM, ck_size) => self.read_check_fmt_pcm(ck_size, &info)?,
-            (WavFmtTag::WAVE_FORMAT_IEEE_FLOAT, ck_size) => self.read_check_fmt_ieee_float(ck_size, &info)?,
-            (WavFmtTag::WAVE_FORMAT_ALAW, ck_size) => self.read_check_fmt_alaw(ck_size, &info)?,
-            (WavFmtTag::WAVE_FORMAT_MULAW, ck_size) => self.read_check_fmt_mulaw(ck_size, &info)?,
-            (WavFmtTag::WAVE_FORMAT_EXTENSIBLE, ck_size) => self.read_check_fmt_extensible(ck_size, &mut info)?,
+        Ok(data)
+    }
 
-            _ => return Err(Error::WrongFmt),
+    fn skip_chunk(&mut self, id: [u8; 4]) -> Result<WavChunkInfo> {
+        let ck_size = self.read_u32()?;
+        let chunk = WavChunkInfo {
+            id,
+            size: ck_size,
+            position: self.position,
         };
-        
-        // 4 bytes for the ck_id.
-        // 4 bytes for the ck_size.
-        let bytes_to_skip = (ck_size + 8) - self.cursor;
-        self.cursor += bytes_to_skip;
 
-        // 4 bytes for the ck_id.
-        // 4 bytes for the ck_size.
-        assert_eq!(self.cursor, 8 + ck_size);
+        self.skip_bytes(padded(ck_size as usize))?;
 
-        Ok(info)
+        Ok(chunk)
     }
     
-    fn read_check_fmt_pcm(&mut self, ck_size :usize, fmt: &AudioInfo) -> Result<()> {
-        // If ck_size is 16, that means that all the fmt was read.
-        if ck_size == 16 { return Ok(()); }
-
-        // If this executes then it means that is a WAVEFORMATEX.
-        // Dealing with cb_size.
-        self.skip_next_bytes::<2>()?;
-        
-        // Dealing with bits_per_sample.
-        if fmt.bits_per_sample > 24 || fmt.bits_per_sample < 8 { 
-            return Err(Error::WrongFmtInfo("Invalid bits_per_sample for PCM format!".to_string()));
+    fn read_fact_chunk(&mut self) -> Result<u64> {
+        let ck_size = self.read_u32()? as usize;
+        if ck_size < 4 {
+            return Err(Error::WrongFmtInfo("fact chunk must have ck_size of at least 4!".to_string()));
         }
+
+        let sample_length = match (self.read_u32()?, self.ds64) {
+            (RF64_SIZE, Some(ds64)) => ds64.sample_count,
+            (sample_length, _) => sample_length as u64,
+        };
+        self.skip_bytes(padded(ck_size) - 4)?;
         
-        Ok(())
+        Ok(sample_length)
     }
-
-    fn read_check_fmt_ieee_float(&mut self, ck_size :usize, _: &AudioInfo) -> Result<()> {
-        // If ck_size is 16, that means that all the fmt was read.
-        if ck_size == 16 { return Ok(()); }
-        if ck_size != 18 { 
-            return Err(Error::WrongFmtInfo("IEEE_FLOAT does not alow for ck_size > 18!".to_string())); 
-        }
-
-        // Dealing with cb_size.
-        if self.read_le_u16()? != 0 {
-            return Err(Error::WrongFmtInfo("IEEE_FLOAT must have cb_size of 0!".to_string()));
+}
+impl<R: io::Read> LgDataReader for LgWavReader<R> {
+    fn read_up_to(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let len = buffer.len().min(self.max_size - self.cursor);
+        let mut read = 0;
+
+        while read < len {
+            match self.reader.read(&mut buffer[read..len]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(Error::Io(e)),
+            }
         }
+        self.cursor += read;
+        self.position += read as u64;
 
-        Ok(())
-    }
-    
-    #[allow(unused)]
-    fn read_check_fmt_alaw(&mut self, ck_size :usize, fmt: &AudioInfo) -> Result<()> {
-        todo!()
+        Ok(read)
     }
-
-    #[allow(unused)]
-    fn read_check_fmt_mulaw(&mut self, ck_size :usize, fmt: &AudioInfo) -> Result<()> {
-        todo!()
+}
+impl<R: io::Read> LgWavReader<R> {
+    /// Reads a chunk level value in the byte order of the file.
+    fn read_u32(&mut self) -> Result<u32> {
+        match self.byte_order {
+            ByteOrder::LITTLE_ENDIAN => self.read_le_u32(),
+            ByteOrder::BIG_ENDIAN => self.read_be_u32(),
+        }
     }
 
-    fn read_check_fmt_extensible(&mut self, ck_size :usize, fmt: &mut AudioInfo) -> Result<()> {
-        if ck_size < 40 {
-            return Err(Error::WrongFmtInfo("WAVE_FORMAT_EXTENSIBLE must have ck_size of 40!".to_string()));
-        }
-        
-        // Dealing with cb_size.
-        if self.read_le_u16()? != 22 {
-            return Err(Error::WrongFmtInfo("WAVE_FORMAT_EXTENSIBLE must have cb_size of 22!".to_string()));
-        }
+    fn skip_bytes(&mut self, n: usize) -> Result<()> {
+        self.move_cursor(n)?;
         
-        let valid_bits_per_sample = self.read_le_u16()?;
-        // Skip channel_mask.
-        self.skip_next_bytes::<4>()?;
-        // GUID
-        let _sub_format: [u8; 16] = self.read_next_bytes()?;
-
-        // TODO: Support different GUIDs.
-
-        if valid_bits_per_sample > 0 {
-            fmt.bits_per_sample = valid_bits_per_sample;
+        let skipped = io::copy(&mut io::Read::take(&mut self.reader, n as u64), &mut io::sink())?;
+        if skipped != n as u64 {
+            return Err(Error::Io(io::Error::new::<String>(io::ErrorKind::UnexpectedEof, "".into())));
         }
 
         Ok(())
     }
 
-    fn read_fact_chunk(&mut self) -> Result<()> {
-        let ck_size = self.read_le_u32()? as usize;
-        let mut _skip_bytes = vec![0u8; ck_size];
-        
-        self.read_into(&mut _skip_bytes)?;
-        
-        Ok(())
-    }
-}
-impl<R: io::Read> LgWavReader<R> {
     fn move_cursor(&mut self, n: usize) -> Result<()> {
-        if self.cursor + n > self.max_size + 1 {
+        if self.cursor + n > self.max_size {
             return Err(Error::Io(io::Error::new::<String>(io::ErrorKind::UnexpectedEof, "".into())));
         }
         
         self.cursor += n;
+        self.position += n as u64;
 
         Ok(())
     }
 }
+impl<R: io::Read + io::Seek> LgWavReader<R> {
+    /// Jumps to the chunk at `position`, any chunk from there until the end of the RIFF chunk can be read.
+    pub(super) fn seek_chunk(&mut self, position: u64) -> Result<()> {
+        self.reader.seek(io::SeekFrom::Start(position))?;
+        self.position = position;
+        self.cursor = 0;
+        self.max_size = self.riff_end.saturating_sub(position) as usize;
 
-fn check_fmt(fmt: &AudioInfo) -> Result<()> {
-    if fmt.channels == 0 {
-        return Err(Error::WrongFmtInfo("fmt.channels must be > 0!".to_string()));
+        Ok(())
     }
-    
-    if fmt.bits_per_sample % 8 != 0 || fmt.bits_per_sample == 0 {
-        return Err(Error::WrongFmtInfo("bits_per_sample must be non 0 and a multiple of 8!".to_string()));
+
+    /// The length of a data chunk of UNKNOWN_DATA_SIZE, everything until the end of the file.
+    pub(super) fn data_len_to_end(&mut self) -> Result<u64> {
+        let end = self.reader.seek(io::SeekFrom::End(0))?;
+        self.reader.seek(io::SeekFrom::Start(self.data_start))?;
+        
+        let data_len = end.saturating_sub(self.data_start);
+        self.data_len = data_len as usize;
+        self.max_size = self.data_len;
+
+        Ok(data_len)
+    }
+
+    /// Where the chunk after the data chunk starts, from the start of the file.
+    pub(super) fn data_end(&self) -> u64 {
+        self.data_start + padded(self.data_len) as u64
     }
 
-    Ok(())
-}
\ No newline at end of file
+    /// Jumps back into the data chunk, `offset` bytes after the first sample.
+    pub(super) fn seek_data(&mut self, offset: usize) -> Result<()> {
+        let offset = offset.min(self.data_len);
+        let position = self.data_start + offset as u64;
+        
+        self.reader.seek(io::SeekFrom::Start(position))?;
+        self.position = position;
+        self.cursor = offset;
+        self.max_size = self.data_len;
+
+        Ok(())
+    }
+}
+
+/// Chunks with an odd size are followed by a padding byte.
+#[inline(always)]
+fn padded(ck_size: usize) -> usize {
+    ck_size + (ck_size & 1)
+}
@@ -0,0 +1,319 @@
+use crate::{reader::LgReader, Result};
+
+/// Size of the smpl chunk without the loops and the sampler data.
+const SMPL_FIXED_SIZE: usize = 36;
+/// Size of each loop in the smpl chunk.
+const SMPL_LOOP_SIZE: usize = 24;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavLoopType {
+    #[default]
+    FORWARD,
+    /// Forward and then backward.
+    ALTERNATING,
+    BACKWARD,
+    OTHER(u32),
+}
+impl From<u32> for WavLoopType {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::FORWARD,
+            1 => Self::ALTERNATING,
+            2 => Self::BACKWARD,
+            _ => Self::OTHER(value),
+        }
+    }
+}
+impl From<WavLoopType> for u32 {
+    fn from(value: WavLoopType) -> Self {
+        match value {
+            WavLoopType::FORWARD => 0,
+            WavLoopType::ALTERNATING => 1,
+            WavLoopType::BACKWARD => 2,
+            WavLoopType::OTHER(value) => value,
+        }
+    }
+}
+
+/// A loop of the smpl chunk, positions are in sample frames.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavSampleLoop {
+    /// Id of the cue point the loop refers to, if any.
+    pub cue_id: u32,
+    pub loop_type: WavLoopType,
+    pub start: u32,
+    /// The last sample frame played in the loop.
+    pub end: u32,
+    /// Fraction of a sample frame, 0x80000000 is half of it.
+    pub fraction: u32,
+    /// 0 loops forever.
+    pub play_count: u32,
+}
+
+/// Sampler information (smpl chunk).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct WavSampler {
+    /// MIDI Manufacturers Association code, 0 for none.
+    pub manufacturer: u32,
+    pub product: u32,
+    /// Nanoseconds per sample frame, 0 to use the sample rate of the file.
+    pub sample_period: u32,
+    /// MIDI note played back at the original pitch, 60 is middle C.
+    pub midi_unity_note: u32,
+    /// Fraction of a semitone above the unity note, 0x80000000 is 50 cents.
+    pub midi_pitch_fraction: u32,
+    pub smpte_format: u32,
+    pub smpte_offset: u32,
+    pub loops: Vec<WavSampleLoop>,
+    /// Data specific to the manufacturer.
+    pub sampler_data: Vec<u8>,
+}
+impl WavSampler {
+    /// What is missing from a short chunk is read as zeroes.
+    pub(super) fn read(data: &[u8]) -> Result<Self> {
+        let mut fixed = [0; SMPL_FIXED_SIZE];
+        let fixed_size = data.len().min(SMPL_FIXED_SIZE);
+        fixed[..fixed_size].copy_from_slice(&data[..fixed_size]);
+        let mut reader = &fixed[..];
+
+        let manufacturer = reader.read_le_u32()?;
+        let product = reader.read_le_u32()?;
+        let sample_period = reader.read_le_u32()?;
+        let midi_unity_note = reader.read_le_u32()?;
+        let midi_pitch_fraction = reader.read_le_u32()?;
+        let smpte_format = reader.read_le_u32()?;
+        let smpte_offset = reader.read_le_u32()?;
+        let loop_count = reader.read_le_u32()? as usize;
+        let sampler_data_size = reader.read_le_u32()? as usize;
+        let mut reader = &data[fixed_size..];
+
+        // Do not trust the count more than the chunk size.
+        let loop_count = loop_count.min(reader.len() / SMPL_LOOP_SIZE);
+        let mut loops = Vec::with_capacity(loop_count);
+        for _ in 0..loop_count {
+            loops.push(WavSampleLoop {
+                cue_id: reader.read_le_u32()?,
+                loop_type: reader.read_le_u32()?.into(),
+                start: reader.read_le_u32()?,
+                end: reader.read_le_u32()?,
+                fraction: reader.read_le_u32()?,
+                play_count: reader.read_le_u32()?,
+            });
+        }
+
+        Ok(Self {
+            manufacturer,
+            product,
+            sample_period,
+            midi_unity_note,
+            midi_pitch_fraction,
+            smpte_format,
+            smpte_offset,
+            loops,
+            sampler_data: reader[..sampler_data_size.min(reader.len())].to_vec(),
+        })
+    }
+
+    /// The smpl chunk data.
+    pub(super) fn to_chunk_data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(SMPL_FIXED_SIZE + self.loops.len() * SMPL_LOOP_SIZE + self.sampler_data.len());
+
+        for value in [
+            self.manufacturer,
+            self.product,
+            self.sample_period,
+            self.midi_unity_note,
+            self.midi_pitch_fraction,
+            self.smpte_format,
+            self.smpte_offset,
+            self.loops.len() as u32,
+            self.sampler_data.len() as u32,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        for sample_loop in &self.loops {
+            for value in [
+                sample_loop.cue_id,
+                sample_loop.loop_type.into(),
+                sample_loop.start,
+                sample_loop.end,
+                sample_loop.fraction,
+                sample_loop.play_count,
+            ] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        data.extend_from_slice(&self.sampler_data);
+
+        data
+    }
+}
+
+/// Size of the inst chunk, without the padding byte.
+const INST_SIZE: usize = 7;
+
+/// Instrument information (inst chunk), notes are MIDI notes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavInstrument {
+    /// Note played back at the original pitch.
+    pub unshifted_note: u8,
+    /// Pitch adjustment in cents, -50 to 50.
+    pub fine_tune: i8,
+    /// Gain in dB.
+    pub gain: i8,
+    pub low_note: u8,
+    pub high_note: u8,
+    pub low_velocity: u8,
+    pub high_velocity: u8,
+}
+impl Default for WavInstrument {
+    fn default() -> Self {
+        Self {
+            unshifted_note: 60,
+            fine_tune: 0,
+            gain: 0,
+            low_note: 0,
+            high_note: 127,
+            low_velocity: 1,
+            high_velocity: 127,
+        }
+    }
+}
+impl WavInstrument {
+    /// What is missing from a short chunk is read as zeroes.
+    pub(super) fn read(data: &[u8]) -> Result<Self> {
+        let mut fixed = [0; INST_SIZE];
+        let fixed_size = data.len().min(INST_SIZE);
+        fixed[..fixed_size].copy_from_slice(&data[..fixed_size]);
+        let mut data = &fixed[..];
+
+        // read_le_i8 is meant for 8 bit PCM, which is stored unsigned.
+        Ok(Self {
+            unshifted_note: data.read_le_u8()?,
+            fine_tune: data.read_le_u8()? as i8,
+            gain: data.read_le_u8()? as i8,
+            low_note: data.read_le_u8()?,
+            high_note: data.read_le_u8()?,
+            low_velocity: data.read_le_u8()?,
+            high_velocity: data.read_le_u8()?,
+        })
+    }
+
+    /// The inst chunk data.
+    pub(super) fn to_chunk_data(self) -> Vec<u8> {
+        vec![
+            self.unshifted_note,
+            self.fine_tune as u8,
+            self.gain as u8,
+            self.low_note,
+            self.high_note,
+            self.low_velocity,
+            self.high_velocity,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use crate::{encoder::LgEncoder, SampleType};
+    use crate::wav::fixtures::{info, samples};
+    use crate::wav::{LgWavDecoder, LgWavEncoder, WavContainer};
+    use super::{WavInstrument, WavLoopType, WavSampleLoop, WavSampler};
+
+    fn sampler() -> WavSampler {
+        WavSampler {
+            manufacturer: 0x47,
+            product: 2,
+            sample_period: 125_000,
+            midi_unity_note: 64,
+            midi_pitch_fraction: 0x8000_0000,
+            loops: vec![
+                WavSampleLoop {
+                    cue_id: 1,
+                    start: 100,
+                    end: 499,
+                    ..Default::default()
+                },
+                WavSampleLoop {
+                    cue_id: 2,
+                    loop_type: WavLoopType::ALTERNATING,
+                    start: 500,
+                    end: 799,
+                    fraction: 0x4000_0000,
+                    play_count: 3,
+                },
+                WavSampleLoop {
+                    loop_type: WavLoopType::OTHER(32),
+                    start: 800,
+                    end: 999,
+                    ..Default::default()
+                },
+            ],
+            sampler_data: vec![1, 2, 3],
+            ..Default::default()
+        }
+    }
+
+    fn instrument() -> WavInstrument {
+        WavInstrument {
+            unshifted_note: 64,
+            fine_tune: -20,
+            gain: -6,
+            low_note: 48,
+            high_note: 72,
+            low_velocity: 10,
+            high_velocity: 100,
+        }
+    }
+
+    fn encode(before_data: bool) -> Vec<u8> {
+        let mut encoder = LgWavEncoder::from_writer(io::Cursor::new(Vec::new()), info(SampleType::INT, 16), WavContainer::RIFF).unwrap();
+        if before_data {
+            encoder.write_sampler(&sampler()).unwrap();
+            encoder.write_instrument(&instrument()).unwrap();
+        }
+        encoder.encode_samples(&samples(2000)).unwrap();
+        if !before_data {
+            encoder.write_sampler(&sampler()).unwrap();
+            encoder.write_instrument(&instrument()).unwrap();
+        }
+
+        encoder.into_inner().unwrap().into_inner()
+    }
+
+    #[test]
+    fn loop_types() {
+        for value in [0, 1, 2, 32] {
+            assert_eq!(u32::from(WavLoopType::from(value)), value);
+        }
+        assert_eq!(WavLoopType::from(2), WavLoopType::BACKWARD);
+        assert_eq!(WavLoopType::from(32), WavLoopType::OTHER(32));
+    }
+
+    #[test]
+    fn sampler_round_trip() {
+        let file = encode(true);
+        let decoder = LgWavDecoder::from_reader(&file[..]).unwrap();
+        assert_eq!(decoder.sampler(), Some(&sampler()));
+        assert_eq!(decoder.instrument(), Some(&instrument()));
+
+        // The odd sized inst chunk and its padding byte trail the data.
+        let decoder = LgWavDecoder::from_seekable(io::Cursor::new(encode(false))).unwrap();
+        assert_eq!(decoder.sampler(), Some(&sampler()));
+        assert_eq!(decoder.instrument(), Some(&instrument()));
+    }
+
+    #[test]
+    fn short_chunks_read_as_zeroes() {
+        let data = sampler().to_chunk_data();
+        // Cut in the middle of the second loop.
+        let short = WavSampler::read(&data[..36 + 24 + 10]).unwrap();
+        assert_eq!(short.loops, sampler().loops[..1]);
+
+        let instrument = WavInstrument::read(&[64, 0xEC]).unwrap();
+        assert_eq!(instrument.fine_tune, -20);
+        assert_eq!(instrument.high_velocity, 0);
+    }
+}
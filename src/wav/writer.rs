@@ -1,13 +1,39 @@
-use std::io;
-use crate::{writer::LgWriter, Result, Sample, SampleType, AudioInfo};
-use super::{WAVE_FORMAT_EXTENSIBLE, WAVE_FORMAT_IEEE_FLOAT, WAVE_FORMAT_PCM};
+use std::io::{self, Seek, Write};
+use crate::{error::Error, writer::{BigEndian, LgSeekWriter, LgWriter}, ByteOrder, Result, Sample, SampleType, AudioInfo};
+use super::adpcm::{self, ImaAdpcmWriter};
+use super::{fmt, write_sub_chunk, WavContainer};
 
 const RIFF_CK_SIZE_POSITION: usize = 4;
+/// The ds64 chunk always comes right after "WAVE".
+const DS64_POSITION: usize = 12;
+/// Size of the ds64 chunk data, without a table.
+const DS64_CK_SIZE: u32 = 28;
+/// RF64 files put this in the 32 bit sizes that are stored in the ds64 chunk instead.
+const RF64_SIZE: u32 = 0xFFFFFFFF;
+/// Samples converted at a time by [`write_sample_parts`].
+const WRITE_SAMPLES_PART: usize = 16384;
+/// Streamed files that don't know their length put this in the sizes.
+const UNKNOWN_SIZE: u32 = 0xFFFFFFFF;
 
 pub struct LgWavWriter<W: io::Write + io::Seek> {
-    pub(super) writer: W,
-    pub(super) data_bytes_written: u32,
-    pub(super) data_ck_size_position: usize,
+    pub(super) writer: Output<W>,
+    container: WavContainer,
+    /// Where the file starts in the writer, the header positions are relative to it.
+    start: usize,
+    pub(super) data_bytes_written: u64,
+    pub(super) samples_written: u64,
+    /// Only present once the data chunk was started.
+    pub(super) data_ck_size_position: Option<usize>,
+    /// Position of dwSampleLength, only present for non PCM formats.
+    pub(super) fact_sample_length_position: Option<usize>,
+    pub(super) channels: u16,
+    /// Only present for IMA_ADPCM.
+    pub(super) adpcm: Option<ImaAdpcmWriter>,
+    /// Raw samples of [`LgWavWriter::write_samples`], kept to avoid allocating every call.
+    bytes: Vec<u8>,
+    /// Chunks that were added after the data chunk started, they are written when finishing.
+    trailing_chunks: Vec<u8>,
+    finished: bool,
 }
 impl<W: io::Write + io::Seek> Drop for LgWavWriter<W> {
     fn drop(&mut self) {
@@ -15,160 +41,721 @@ impl<W: io::Write + io::Seek> Drop for LgWavWriter<W> {
     }
 }
 impl<W: io::Write + io::Seek> LgWavWriter<W> {
-    pub fn new(writer: W, info: &AudioInfo) -> Result<Self> {
+    pub fn new(mut writer: W, info: &AudioInfo, container: WavContainer) -> Result<Self> {
+        // Before anything is written, Drop would finish a file that can't have headers.
+        fmt::check_info(info)?;
+        let start = writer.stream_position()? as usize;
+
         let mut result = Self {
-            writer,
+            writer: Output(Some(writer)),
+            container,
+            start,
             data_bytes_written: 0,
-            data_ck_size_position: 0,
+            samples_written: 0,
+            data_ck_size_position: None,
+            fact_sample_length_position: None,
+            channels: info.channels,
+            adpcm: None,
+            bytes: Vec::new(),
+            trailing_chunks: Vec::new(),
+            finished: false,
         };
         
         result.write_header()?;
         result.write_fmt_chunk(info)?;
-        result.writer.write(b"data")?;
-        result.writer.write_le_u32(0)?;
 
         Ok(result)
     }
     
     #[inline(always)]
     pub fn write_sample<S: Sample>(&mut self, sample: S, sample_type: SampleType, bits_per_sample: u16) -> Result<()> {
-        sample.write(&mut self.writer, sample_type, bits_per_sample)?;
-        self.data_bytes_written += bits_per_sample as u32 / 8;
+        if self.data_ck_size_position.is_none() {
+            self.start_data()?;
+        }
+
+        match &mut self.adpcm {
+            Some(adpcm) => self.data_bytes_written += adpcm.push(sample, &mut self.writer)?,
+            None => {
+                match self.container.byte_order() {
+                    ByteOrder::LITTLE_ENDIAN => sample.write(&mut self.writer, sample_type, bits_per_sample)?,
+                    ByteOrder::BIG_ENDIAN => sample.write(&mut BigEndian(&mut self.writer), sample_type, bits_per_sample)?,
+                }
+                self.data_bytes_written += bits_per_sample as u64 / 8;
+            },
+        }
+        self.samples_written += 1;
         
         Ok(())
     }
 
+    /// Converts whole blocks of samples and writes each one at once.
+    pub fn write_samples<S: Sample + Copy>(&mut self, samples: &[S], sample_type: SampleType, bits_per_sample: u16) -> Result<()> {
+        // ADPCM is already encoded a block at a time.
+        if self.adpcm.is_some() {
+            for &sample in samples {
+                self.write_sample(sample, sample_type, bits_per_sample)?;
+            }
+
+            return Ok(());
+        }
+
+        if self.data_ck_size_position.is_none() {
+            self.start_data()?;
+        }
+
+        let byte_order = self.container.byte_order();
+        self.data_bytes_written += write_sample_parts(&mut self.writer, &mut self.bytes, samples, sample_type, bits_per_sample, byte_order)?;
+        self.samples_written += samples.len() as u64;
+
+        Ok(())
+    }
+
+    /// Writes the chunk before the data chunk if no sample was written yet, otherwise it is written after 
+    /// the data chunk when finishing.
+    pub fn write_chunk(&mut self, id: &[u8; 4], data: &[u8]) -> Result<()> {
+        if self.container == WavContainer::RIFX {
+            return Err(Error::WrongFmtInfo("Metadata chunks can not be written to RIFX files!".to_string()));
+        }
+
+        if self.data_ck_size_position.is_some() {
+            write_sub_chunk(&mut self.trailing_chunks, id, data);
+            return Ok(());
+        }
+
+        self.write_chunk_now(id, data)
+    }
+
     pub fn flush(&mut self) -> Result<()> {
-        let current_pos = self.writer.seek(io::SeekFrom::Current(0))?;
-        self.update_headers()?;
+        let current_pos = self.writer.stream_position()?;
+        self.update_headers(current_pos)?;
         self.writer.flush()?;
         self.writer.go_to(current_pos as usize)?;
         
         Ok(())
     }
     
+    /// Only the first call does anything, even if it fails, so a file is never finished twice.
     pub fn finish(&mut self) -> Result<()> {
-        self.update_headers()?;
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+
+        if self.data_ck_size_position.is_none() {
+            self.start_data()?;
+        }
+
+        // The last ADPCM block is only written once we know no more samples are coming.
+        if let Some(adpcm) = &mut self.adpcm {
+            self.data_bytes_written += adpcm.finish(&mut self.writer)?;
+        }
+
+        // Padding byte of the data chunk.
+        if self.data_bytes_written % 2 == 1 {
+            self.writer.write_le_u8(0)?;
+        }
+        self.writer.write_all(&self.trailing_chunks)?;
+        self.trailing_chunks.clear();
+
+        let end = self.writer.stream_position()?;
+        self.update_headers(end)?;
+        self.writer.go_to(end as usize)?;
         self.writer.flush()?;
         
         Ok(())
     }
+
+    /// Finishes the file and returns the writer, positioned at the end of it.
+    pub fn into_inner(mut self) -> Result<W> {
+        self.finish()?;
+
+        Ok(self.writer.take()?)
+    }
 }
 impl<W: io::Write + io::Seek> LgWavWriter<W> {
     fn write_header(&mut self) -> Result<()> {
-        self.writer.write(b"RIFF")?;
+        match self.container {
+            WavContainer::RIFF => {
+                self.writer.write_all(b"RIFF")?;
+        
+                // Empty for now. (ck_size) - position 4.
+                self.writer.write_le_u32(0)?;
+                self.writer.write_all(b"WAVE")?;
+            },
+            WavContainer::RF64 => {
+                self.writer.write_all(b"RF64")?;
+                self.writer.write_le_u32(RF64_SIZE)?;
+                self.writer.write_all(b"WAVE")?;
 
-        // Empty for now. (ck_size) - position 4.
-        self.writer.write_le_u32(0)?;
-        self.writer.write(b"WAVE")?;
+                self.writer.write_all(b"ds64")?;
+                self.writer.write_le_u32(DS64_CK_SIZE)?;
+                
+                // riff_size, data_size and sample_count, empty for now. Followed by an empty table.
+                self.writer.write_all(&[0; DS64_CK_SIZE as usize])?;
+            },
+            WavContainer::RIFX => {
+                self.writer.write_all(b"RIFX")?;
+        
+                // Empty for now. (ck_size) - position 4.
+                self.writer.write_be_u32(0)?;
+                self.writer.write_all(b"WAVE")?;
+            },
+            WavContainer::RF64_AUTO => {
+                self.writer.write_all(b"RIFF")?;
+        
+                // Empty for now. (ck_size) - position 4.
+                self.writer.write_le_u32(0)?;
+                self.writer.write_all(b"WAVE")?;
+
+                // Takes the place of the ds64 chunk, readers skip it if the file stays RIFF.
+                self.writer.write_all(b"JUNK")?;
+                self.writer.write_le_u32(DS64_CK_SIZE)?;
+                self.writer.write_all(&[0; DS64_CK_SIZE as usize])?;
+            },
+        }
 
         Ok(())
     }
 
     fn write_fmt_chunk(&mut self, info: &AudioInfo) -> Result<()> {
-        self.writer.write(b"fmt ")?;
+        let data = fmt::fmt_chunk_data(info, self.container.byte_order())?;
+        self.write_chunk_now(b"fmt ", &data)?;
 
-        match info.sample_type {
-            Some(SampleType::INT) 
-            | None => if info.channels > 2 {
-                self.write_check_pcm_ex_fmt(info)
-            }
-            else {
-                self.write_check_pcm_fmt(info)
-            },
+        self.adpcm = ImaAdpcmWriter::for_info(info)?;
 
-            Some(SampleType::FLOAT) => self.write_check_ieee_float_fmt(info),
+        if fmt::needs_fact_chunk(info) {
+            self.write_fact_chunk()?;
         }
+
+        Ok(())
     }
     
-    fn write_check_pcm_fmt(&mut self, info: &AudioInfo) -> Result<()> {
-        // Header + fmt header + fmt data + data tag.
-        self.data_ck_size_position = 12 + 8 + 16 + 4;
-        
-        // ck_size of 16.
-        self.writer.write_le_u32(16)?;
+    fn write_chunk_now(&mut self, id: &[u8; 4], data: &[u8]) -> Result<()> {
+        self.writer.write_all(id)?;
+        self.write_u32(data.len() as u32)?;
+        self.writer.write_all(data)?;
 
-        // fmt_tag.
-        self.writer.write_le_u16(WAVE_FORMAT_PCM)?;
+        if data.len() % 2 == 1 {
+            self.writer.write_le_u8(0)?;
+        }
 
-        self.write_fmt(info)
+        Ok(())
+    }
+    
+    fn write_fact_chunk(&mut self) -> Result<()> {
+        self.writer.write_all(b"fact")?;
+        self.write_u32(4)?;
+        self.fact_sample_length_position = Some(self.writer.stream_position()? as usize);
+        
+        // dwSampleLength, empty for now.
+        self.write_u32(0)
     }
 
-    fn write_check_pcm_ex_fmt(&mut self, info: &AudioInfo) -> Result<()> {
-        // Header + fmt header + fmt data + data tag.
-        self.data_ck_size_position = 12 + 8 + 40 + 4;
+    fn start_data(&mut self) -> Result<()> {
+        self.writer.write_all(b"data")?;
+        self.data_ck_size_position = Some(self.writer.stream_position()? as usize);
         
-        // ck_size of 40.
-        self.writer.write_le_u32(40)?;
+        // Empty for now.
+        self.write_u32(0)
+    }
 
-        // fmt_tag.
-        self.writer.write_le_u16(WAVE_FORMAT_EXTENSIBLE)?;
+    /// Writes a chunk level value in the byte order of the container.
+    fn write_u32(&mut self, data: u32) -> Result<()> {
+        match self.container.byte_order() {
+            ByteOrder::LITTLE_ENDIAN => self.writer.write_le_u32(data),
+            ByteOrder::BIG_ENDIAN => self.writer.write_be_u32(data),
+        }
+    }
 
-        self.write_fmt(info)?;
-            
-        // cb_size.
-        self.writer.write_le_u16(22)?;
-        
-        // valid_bits_per_sample.
-        self.writer.write_le_u16(info.bits_per_sample)?;
+    /// `end` is where the file ends.
+    fn update_headers(&mut self, end: u64) -> Result<()> {
+        // Everything but "RIFF" and ck_size.
+        let riff_size = end - self.start as u64 - 8;
+        let sample_frames = self.samples_written / self.channels as u64;
         
-        // channel_mask.
-        let channels = if info.channels > 18 { 18 } else { info.channels };
-        self.writer.write_le_u32(channels as u32)?;
+        let is_rf64 = match self.container {
+            WavContainer::RIFF | WavContainer::RIFX => if riff_size > u32::MAX as u64 {
+                return Err(Error::WrongFmtInfo("RIFF files can not be bigger than 4 GiB, use WavContainer::RF64!".to_string()));
+            }
+            else {
+                false
+            },
+            WavContainer::RF64 => true,
+            WavContainer::RF64_AUTO => riff_size > u32::MAX as u64,
+        };
+
+        if is_rf64 {
+            // Already there for RF64, for RF64_AUTO this turns the RIFF header and the JUNK chunk into RF64.
+            self.writer.go_to(self.start)?;
+            self.writer.write_all(b"RF64")?;
+            self.writer.write_le_u32(RF64_SIZE)?;
+            self.writer.go_to(self.start + DS64_POSITION)?;
+            self.writer.write_all(b"ds64")?;
+            self.writer.write_le_u32(DS64_CK_SIZE)?;
+
+            // riff_size, data_size and sample_count.
+            self.writer.write_le_u64(riff_size)?;
+            self.writer.write_le_u64(self.data_bytes_written)?;
+            self.writer.write_le_u64(sample_frames)?;
+        }
+        else {
+            // RIFF ck_size.
+            self.writer.go_to(self.start + RIFF_CK_SIZE_POSITION)?;
+            self.write_u32(riff_size as u32)?;
+        }
         
-        // sub_format.
-        self.writer.write(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80,
-            0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71])?;
+        // fact dwSampleLength.
+        if let Some(position) = self.fact_sample_length_position {
+            self.writer.go_to(position)?;
+            self.write_u32(u32::try_from(sample_frames).unwrap_or(RF64_SIZE))?;
+        }
         
+        // Data ck_size.
+        if let Some(position) = self.data_ck_size_position {
+            let data_size = if is_rf64 { RF64_SIZE } else { self.data_bytes_written as u32 };
+
+            self.writer.go_to(position)?;
+            self.write_u32(data_size)?;
+        }
+
         Ok(())
     }
+}
 
-    fn write_check_ieee_float_fmt(&mut self, info: &AudioInfo) -> Result<()> {
-        // Header + fmt header + fmt data + data tag.
-        self.data_ck_size_position = 12 + 8 + 18 + 4;
+/// Converts whole parts of `samples` and writes each one at once, returns the bytes written.
+/// `bytes` holds the raw samples of a part, the writers keep it to avoid allocating every call.
+pub(crate) fn write_sample_parts<S: Sample + Copy>(
+    writer: &mut impl io::Write, 
+    bytes: &mut Vec<u8>, 
+    samples: &[S], 
+    sample_type: SampleType, 
+    bits_per_sample: u16, 
+    byte_order: ByteOrder
+) -> Result<u64> {
+    let bytes_per_sample = bits_per_sample as usize / 8;
+    let mut written = 0;
 
-        // ck_size of 18.
-        self.writer.write_le_u32(18)?;
-        
-        // fmt_tag.
-        self.writer.write_le_u16(WAVE_FORMAT_IEEE_FLOAT)?;
-        
-        self.write_fmt(info)?;
-        
-        // cb_size.
-        self.writer.write_le_u16(0)
+    // Big slices are converted in parts, so the raw samples don't take as much memory as them.
+    for part in samples.chunks(WRITE_SAMPLES_PART) {
+        bytes.clear();
+        S::write_slice(part, sample_type, bits_per_sample, bytes)?;
+
+        if byte_order == ByteOrder::BIG_ENDIAN {
+            bytes.chunks_exact_mut(bytes_per_sample).for_each(|sample| sample.reverse());
+        }
+        writer.write_all(bytes)?;
+
+        written += bytes.len() as u64;
     }
-    
-    fn write_fmt(&mut self, info: &AudioInfo) -> Result<()> {
-        // n_channels.
-        self.writer.write_le_u16(info.channels)?;
 
-        // samples_per_sec.
-        self.writer.write_le_u32(info.sample_rate)?;
-        
-        // avg_bytes_per_sec.
-        let bytes_per_sec = info.sample_rate
-            * (info.bits_per_sample / 8) as u32
-            * info.channels as u32;
+    Ok(written)
+}
 
-        self.writer.write_le_u32(bytes_per_sec)?;
-        
-        // block_align.
-        self.writer.write_le_u16((bytes_per_sec / info.sample_rate) as u16)?;
-            
-        // bits_per_sample.
-        self.writer.write_le_u16(info.bits_per_sample)
+/// The output of [`LgWavWriter`] and [`crate::w64::writer::LgW64Writer`], it can be taken out by 
+/// their `into_inner` even though the writers finish the file on drop.
+pub(crate) struct Output<W>(pub(crate) Option<W>);
+impl<W: io::Write> io::Write for Output<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner()?.write(buf)
     }
 
-    fn update_headers(&mut self) -> Result<()> {
-        let file_size = self.data_bytes_written + self.data_ck_size_position as u32 - 4;
-        
-        // RIFF ck_size.
-        self.writer.go_to(RIFF_CK_SIZE_POSITION)?;
-        self.writer.write_le_u32(file_size)?;
-        
-        // Data ck_size.
-        self.writer.go_to(self.data_ck_size_position)?;
-        self.writer.write_le_u32(self.data_bytes_written)
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner()?.flush()
+    }
+}
+impl<W: io::Seek> io::Seek for Output<W> {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.inner()?.seek(pos)
+    }
+}
+impl<W> Output<W> {
+    fn inner(&mut self) -> io::Result<&mut W> {
+        self.0.as_mut().ok_or_else(|| io::Error::other("the writer was taken by into_inner"))
+    }
+
+    pub(crate) fn take(&mut self) -> io::Result<W> {
+        self.0.take().ok_or_else(|| io::Error::other("the writer was taken by into_inner"))
+    }
+}
+
+/// Writes WAV files without ever seeking, for outputs like pipes and sockets. 
+/// The sizes are written up front, either from a declared length or as unknown.
+pub struct LgWavStreamWriter<W: io::Write> {
+    pub(super) writer: W,
+    pub(super) data_bytes_written: u64,
+    pub(super) samples_written: u64,
+    /// Only present when the length was declared.
+    sample_len: Option<u64>,
+    /// Only present for IMA_ADPCM.
+    adpcm: Option<ImaAdpcmWriter>,
+    /// Raw samples of [`LgWavStreamWriter::write_samples`], kept to avoid allocating every call.
+    bytes: Vec<u8>,
+    finished: bool,
+}
+impl<W: io::Write> Drop for LgWavStreamWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+impl<W: io::Write> LgWavStreamWriter<W> {
+    /// `sample_len` is the number of samples that will be written, when it is not known 
+    /// the sizes are set to 0xFFFFFFFF and readers go until the end of the file.
+    pub fn new(writer: W, info: &AudioInfo, sample_len: Option<u64>) -> Result<Self> {
+        fmt::check_info(info)?;
+
+        let mut result = Self {
+            writer,
+            data_bytes_written: 0,
+            samples_written: 0,
+            sample_len,
+            adpcm: None,
+            bytes: Vec::new(),
+            finished: false,
+        };
+
+        result.write_header(info)?;
+
+        Ok(result)
+    }
+
+    #[inline(always)]
+    pub fn write_sample<S: Sample>(&mut self, sample: S, sample_type: SampleType, bits_per_sample: u16) -> Result<()> {
+        if self.sample_len.is_some_and(|len| self.samples_written >= len) {
+            return Err(Error::WrongFmtInfo("Can not write more samples than the declared length!".to_string()));
+        }
+
+        match &mut self.adpcm {
+            Some(adpcm) => self.data_bytes_written += adpcm.push(sample, &mut self.writer)?,
+            None => {
+                sample.write(&mut self.writer, sample_type, bits_per_sample)?;
+                self.data_bytes_written += bits_per_sample as u64 / 8;
+            },
+        }
+        self.samples_written += 1;
+
+        Ok(())
+    }
+
+    /// Converts whole blocks of samples and writes each one at once. Nothing is written if the samples 
+    /// don't fit in the declared length.
+    pub fn write_samples<S: Sample + Copy>(&mut self, samples: &[S], sample_type: SampleType, bits_per_sample: u16) -> Result<()> {
+        if self.sample_len.is_some_and(|len| self.samples_written + samples.len() as u64 > len) {
+            return Err(Error::WrongFmtInfo("Can not write more samples than the declared length!".to_string()));
+        }
+
+        // ADPCM is already encoded a block at a time.
+        if self.adpcm.is_some() {
+            for &sample in samples {
+                self.write_sample(sample, sample_type, bits_per_sample)?;
+            }
+
+            return Ok(());
+        }
+
+        self.data_bytes_written += write_sample_parts(&mut self.writer, &mut self.bytes, samples, sample_type, bits_per_sample, ByteOrder::LITTLE_ENDIAN)?;
+        self.samples_written += samples.len() as u64;
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Fails if the declared length was not reached, the header already says otherwise.
+    pub fn finish(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+
+        // The last ADPCM block is only written once we know no more samples are coming.
+        if let Some(adpcm) = &mut self.adpcm {
+            self.data_bytes_written += adpcm.finish(&mut self.writer)?;
+        }
+
+        // Files of unknown length end with the data, so they don't need the padding byte.
+        if self.sample_len.is_some() && self.data_bytes_written % 2 == 1 {
+            self.writer.write_le_u8(0)?;
+        }
+        self.writer.flush()?;
+
+        match self.sample_len {
+            Some(sample_len) if sample_len != self.samples_written => Err(Error::WrongFmtInfo(std::format!(
+                "Declared {} samples but only {} were written!",
+                sample_len,
+                self.samples_written
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+impl<W: io::Write> LgWavStreamWriter<W> {
+    fn write_header(&mut self, info: &AudioInfo) -> Result<()> {
+        let channels = info.channels.max(1) as u64;
+        if self.sample_len.is_some_and(|len| len % channels != 0) {
+            return Err(Error::WrongFmtInfo("The declared length must be a multiple of channels!".to_string()));
+        }
+        let sample_frames = self.sample_len.map(|len| len / channels);
+        let data_size = sample_frames.map(|frames| stream_data_size(info, frames)).transpose()?;
+
+        // Everything after the RIFF ck_size is built first, so the size is known.
+        let mut chunks = b"WAVE".to_vec();
+        write_sub_chunk(&mut chunks, b"fmt ", &fmt::fmt_chunk_data(info, ByteOrder::LITTLE_ENDIAN)?);
+
+        self.adpcm = ImaAdpcmWriter::for_info(info)?;
+
+        if fmt::needs_fact_chunk(info) {
+            let frames = sample_frames.and_then(|frames| u32::try_from(frames).ok()).unwrap_or(UNKNOWN_SIZE);
+            write_sub_chunk(&mut chunks, b"fact", &frames.to_le_bytes());
+        }
+
+        let riff_size = match data_size {
+            Some(data_size) => {
+                // data ck_id, ck_size and the padded data.
+                let riff_size = chunks.len() as u64 + 8 + data_size + (data_size & 1);
+                if riff_size > u32::MAX as u64 {
+                    return Err(Error::WrongFmtInfo("RIFF files can not be bigger than 4 GiB!".to_string()));
+                }
+
+                riff_size as u32
+            },
+            None => UNKNOWN_SIZE,
+        };
+
+        self.writer.write_all(b"RIFF")?;
+        self.writer.write_le_u32(riff_size)?;
+        self.writer.write_all(&chunks)?;
+
+        self.writer.write_all(b"data")?;
+        self.writer.write_le_u32(data_size.map_or(UNKNOWN_SIZE, |data_size| data_size as u32))?;
+
+        Ok(())
+    }
+}
+
+/// Size of the audio data of `sample_frames`, without the padding byte.
+fn stream_data_size(info: &AudioInfo, sample_frames: u64) -> Result<u64> {
+    match info.sample_type {
+        // Every block is full, the last one is padded with silence.
+        Some(SampleType::IMA_ADPCM) => {
+            let block_align = adpcm::ima_block_align(info.channels, info.sample_rate)?;
+            let samples_per_block = adpcm::ima_samples_per_block(info.channels, block_align)? as u64;
+
+            Ok(sample_frames.div_ceil(samples_per_block) * block_align as u64)
+        },
+        _ => Ok(sample_frames * info.channels as u64 * (info.bits_per_sample as u64 / 8)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use crate::{decoder::LgDecoder, encoder::LgEncoder, AudioInfo, SampleType};
+    use crate::wav::fixtures::{decode, encode, info, samples};
+    use crate::wav::{LgWavDecoder, LgWavEncoder, LgWavStreamEncoder, WavContainer, WavMetadata};
+    use super::LgWavWriter;
+
+    #[test]
+    fn rf64_round_trip() {
+        let samples = samples(2000);
+        let file = encode(info(SampleType::INT, 16), WavContainer::RF64, &samples);
+
+        assert_eq!(&file[..4], b"RF64");
+        assert_eq!(&file[4..8], &[0xFF; 4]);
+        assert_eq!(&file[12..16], b"ds64");
+        // riff_size, data_size and sample_count.
+        assert_eq!(u64::from_le_bytes(file[20..28].try_into().unwrap()), file.len() as u64 - 8);
+        assert_eq!(u64::from_le_bytes(file[28..36].try_into().unwrap()), samples.len() as u64 * 2);
+        assert_eq!(u64::from_le_bytes(file[36..44].try_into().unwrap()), samples.len() as u64 / 2);
+
+        assert_eq!(decode(file), samples);
+    }
+
+    #[test]
+    fn rf64_auto_stays_riff() {
+        let samples = samples(2000);
+        let file = encode(info(SampleType::INT, 16), WavContainer::RF64_AUTO, &samples);
+
+        assert_eq!(&file[..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(file[4..8].try_into().unwrap()), file.len() as u32 - 8);
+        assert_eq!(&file[12..16], b"JUNK");
+
+        assert_eq!(decode(file), samples);
+    }
+
+    #[test]
+    fn rf64_auto_promotion() {
+        let samples = samples(2000);
+        let mut writer = LgWavWriter::new(io::Cursor::new(Vec::new()), &info(SampleType::INT, 16), WavContainer::RF64_AUTO).unwrap();
+        writer.write_samples(&samples, SampleType::INT, 16).unwrap();
+        writer.finish().unwrap();
+
+        // Writing more than 4 GiB takes too long, so the headers are updated as if the file ended there.
+        let end = u32::MAX as u64 + 16;
+        writer.update_headers(end).unwrap();
+        let file = writer.into_inner().unwrap().into_inner();
+
+        assert_eq!(&file[..4], b"RF64");
+        assert_eq!(&file[4..8], &[0xFF; 4]);
+        assert_eq!(&file[12..16], b"ds64");
+        assert_eq!(u64::from_le_bytes(file[20..28].try_into().unwrap()), end - 8);
+        assert_eq!(u64::from_le_bytes(file[28..36].try_into().unwrap()), samples.len() as u64 * 2);
+
+        assert_eq!(decode(file), samples);
+    }
+
+    #[test]
+    fn riff_is_not_promoted() {
+        let mut writer = LgWavWriter::new(io::Cursor::new(Vec::new()), &info(SampleType::INT, 16), WavContainer::RIFF).unwrap();
+        writer.write_samples(&samples(2000), SampleType::INT, 16).unwrap();
+        writer.finish().unwrap();
+
+        assert!(writer.update_headers(u32::MAX as u64 + 16).is_err());
+    }
+
+    #[test]
+    fn rifx_round_trip() {
+        let samples = samples(2000);
+        let file = encode(info(SampleType::INT, 16), WavContainer::RIFX, &samples);
+
+        assert_eq!(&file[..4], b"RIFX");
+        assert_eq!(u32::from_be_bytes(file[4..8].try_into().unwrap()), file.len() as u32 - 8);
+        // The samples are big endian too.
+        assert_eq!(file[file.len() - 2..], samples[samples.len() - 1].to_be_bytes());
+
+        assert_eq!(decode(file.clone()), samples);
+
+        let mut decoder = LgWavDecoder::from_seekable(io::Cursor::new(file)).unwrap();
+        let mut buffer = vec![0i16; samples.len()];
+        assert_eq!(decoder.read_samples(&mut buffer).unwrap(), samples.len());
+        assert_eq!(buffer, samples);
+    }
+
+    #[test]
+    fn rifx_limits() {
+        let info = info(SampleType::INT, 16);
+        let mut encoder = LgWavEncoder::from_writer(io::Cursor::new(Vec::new()), info, WavContainer::RIFX).unwrap();
+        assert!(encoder.write_metadata(&WavMetadata::new()).is_err());
+
+        let info = AudioInfo { bits_per_sample: 4, sample_type: Some(SampleType::IMA_ADPCM), ..info };
+        assert!(LgWavEncoder::from_writer(io::Cursor::new(Vec::new()), info, WavContainer::RIFX).is_err());
+    }
+
+    fn encode_stream(info: AudioInfo, sample_len: Option<usize>, samples: &[i16]) -> Vec<u8> {
+        let mut file = Vec::new();
+        let mut encoder = match sample_len {
+            Some(sample_len) => LgWavStreamEncoder::with_len(&mut file, info, sample_len).unwrap(),
+            None => LgWavStreamEncoder::new(&mut file, info).unwrap(),
+        };
+        encoder.encode_samples(samples).unwrap();
+        encoder.finish().unwrap();
+
+        file
+    }
+
+    #[test]
+    fn stream_of_unknown_length() {
+        let samples = samples(2000);
+
+        for info in [info(SampleType::INT, 16), info(SampleType::IMA_ADPCM, 4)] {
+            let file = encode_stream(info, None, &samples);
+            assert_eq!(&file[4..8], &[0xFF; 4]);
+
+            // Nothing says where the data ends, so it is read until the end of the file.
+            let mut decoder = LgWavDecoder::from_reader(&file[..]).unwrap();
+            assert_eq!(decoder.len(), 0);
+
+            let decoded: Vec<i16> = decoder.samples().collect();
+            match info.sample_type {
+                // Without a fact chunk the padding of the last block is decoded too.
+                Some(SampleType::IMA_ADPCM) => assert!(decoded.len() >= samples.len()),
+                _ => assert_eq!(decoded, samples),
+            }
+        }
+    }
+
+    #[test]
+    fn stream_of_declared_length() {
+        let samples = samples(2000);
+        let file = encode_stream(info(SampleType::INT, 16), Some(samples.len()), &samples);
+        assert_eq!(u32::from_le_bytes(file[4..8].try_into().unwrap()), file.len() as u32 - 8);
+
+        let mut decoder = LgWavDecoder::from_reader(&file[..]).unwrap();
+        assert_eq!(decoder.len(), samples.len());
+        assert_eq!(decoder.samples::<i16>().collect::<Vec<_>>(), samples);
+
+        let mut encoder = LgWavStreamEncoder::with_len(Vec::new(), info(SampleType::INT, 16), 2).unwrap();
+        encoder.encode_samples(&[1i16, 2]).unwrap();
+        assert!(encoder.encode_sample(3i16).is_err());
+
+        let mut encoder = LgWavStreamEncoder::with_len(Vec::new(), info(SampleType::INT, 16), 4).unwrap();
+        encoder.encode_samples(&[1i16, 2]).unwrap();
+        assert!(encoder.finish().is_err());
+    }
+
+    #[test]
+    fn wrong_info_is_rejected_before_writing() {
+        let good = info(SampleType::INT, 16);
+
+        for info in [
+            AudioInfo { channels: 0, ..good },
+            AudioInfo { sample_rate: 0, ..good },
+            AudioInfo { bits_per_sample: 12, ..good },
+        ] {
+            let mut file = Vec::new();
+            assert!(LgWavWriter::new(io::Cursor::new(&mut file), &info, WavContainer::RIFF).is_err());
+            assert!(LgWavStreamEncoder::new(&mut file, info).is_err());
+            // Nothing was written, so Drop had nothing to finish.
+            assert!(file.is_empty());
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn file_after_other_data() {
+        let samples = samples(2000);
+
+        for container in [WavContainer::RIFF, WavContainer::RF64, WavContainer::RIFX] {
+            let mut writer = io::Cursor::new(b"other".to_vec());
+            writer.set_position(5);
+
+            let mut encoder = LgWavEncoder::from_writer(writer, info(SampleType::INT, 16), container).unwrap();
+            encoder.encode_samples(&samples).unwrap();
+            let file = encoder.into_inner().unwrap().into_inner();
+
+            // What was there before is left alone, and the file is the same as on its own.
+            assert_eq!(&file[..5], b"other");
+            assert_eq!(file[5..], encode(info(SampleType::INT, 16), container, &samples));
+        }
+    }
+
+    #[test]
+    fn stream_bulk_matches_one_by_one() {
+        let samples = samples(40002);
+
+        for info in [info(SampleType::INT, 24), info(SampleType::FLOAT, 32), info(SampleType::IMA_ADPCM, 4)] {
+            for sample_len in [None, Some(samples.len())] {
+                let mut file = Vec::new();
+                let mut encoder = match sample_len {
+                    Some(sample_len) => LgWavStreamEncoder::with_len(&mut file, info, sample_len).unwrap(),
+                    None => LgWavStreamEncoder::new(&mut file, info).unwrap(),
+                };
+                samples.iter().for_each(|&sample| encoder.encode_sample(sample).unwrap());
+                encoder.finish().unwrap();
+
+                assert!(encode_stream(info, sample_len, &samples) == file);
+            }
+        }
+
+        // Samples past the declared length fail before anything is written.
+        let mut file = Vec::new();
+        let mut encoder = LgWavStreamEncoder::with_len(&mut file, info(SampleType::INT, 16), 2).unwrap();
+        assert!(encoder.encode_samples(&[1i16, 2, 3]).is_err());
+        assert_eq!(encoder.encoded_samples(), 0);
+        encoder.encode_samples(&[1i16, 2]).unwrap();
+        encoder.finish().unwrap();
+        assert_eq!(decode(file), [1, 2]);
+    }
+}
@@ -2,13 +2,13 @@ use std::io;
 use crate::{tools, Result};
 
 pub trait LgWriter {
-    fn go_to(&mut self, position: usize) -> Result<usize>;
-
     fn write_le_u8(&mut self, data: u8) -> Result<()>;
 
     fn write_le_u16(&mut self, data: u16) -> Result<()>;
     
     fn write_le_u32(&mut self, data: u32) -> Result<()>;
+    
+    fn write_le_u64(&mut self, data: u64) -> Result<()>;
 
     fn write_le_i8(&mut self, data: i8) -> Result<()>;
     
@@ -21,27 +21,45 @@ pub trait LgWriter {
     fn write_le_f32(&mut self, data: f32) -> Result<()>;
     
     fn write_le_f64(&mut self, data: f64) -> Result<()>;
-}
 
-impl<W: io::Write + io::Seek> LgWriter for W {
-    fn go_to(&mut self, position: usize) -> Result<usize> {
-        Ok(self.seek(io::SeekFrom::Start(position as u64))? as usize)
-    }
+    fn write_be_u16(&mut self, data: u16) -> Result<()>;
+    
+    fn write_be_u32(&mut self, data: u32) -> Result<()>;
+    
+    fn write_be_u64(&mut self, data: u64) -> Result<()>;
+    
+    fn write_be_i16(&mut self, data: i16) -> Result<()>;
+    
+    fn write_be_i32(&mut self, data: i32) -> Result<()>;
+
+    fn write_be_i32_24(&mut self, data: i32) -> Result<()>;
+    
+    fn write_be_f32(&mut self, data: f32) -> Result<()>;
+    
+    fn write_be_f64(&mut self, data: f64) -> Result<()>;
+}
 
+impl<W: io::Write> LgWriter for W {
     fn write_le_u8(&mut self, data: u8) -> Result<()> {
-        self.write(&[data])?;
+        self.write_all(&[data])?;
 
         Ok(())
     }
 
     fn write_le_u16(&mut self, data: u16) -> Result<()> {
-        self.write(&data.to_le_bytes())?;
+        self.write_all(&data.to_le_bytes())?;
 
         Ok(())
     }
 
     fn write_le_u32(&mut self, data: u32) -> Result<()> {
-        self.write(&data.to_le_bytes())?;
+        self.write_all(&data.to_le_bytes())?;
+
+        Ok(())
+    }
+
+    fn write_le_u64(&mut self, data: u64) -> Result<()> {
+        self.write_all(&data.to_le_bytes())?;
 
         Ok(())
     }
@@ -53,13 +71,13 @@ impl<W: io::Write + io::Seek> LgWriter for W {
     }
 
     fn write_le_i16(&mut self, data: i16) -> Result<()> {
-        self.write(&data.to_le_bytes())?;
+        self.write_all(&data.to_le_bytes())?;
 
         Ok(())
     }
 
     fn write_le_i32(&mut self, data: i32) -> Result<()> {
-        self.write(&data.to_le_bytes())?;
+        self.write_all(&data.to_le_bytes())?;
 
         Ok(())
     }
@@ -82,4 +100,140 @@ impl<W: io::Write + io::Seek> LgWriter for W {
         
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn write_be_u16(&mut self, data: u16) -> Result<()> {
+        self.write_all(&data.to_be_bytes())?;
+
+        Ok(())
+    }
+
+    fn write_be_u32(&mut self, data: u32) -> Result<()> {
+        self.write_all(&data.to_be_bytes())?;
+
+        Ok(())
+    }
+
+    fn write_be_u64(&mut self, data: u64) -> Result<()> {
+        self.write_all(&data.to_be_bytes())?;
+
+        Ok(())
+    }
+
+    fn write_be_i16(&mut self, data: i16) -> Result<()> {
+        self.write_all(&data.to_be_bytes())?;
+
+        Ok(())
+    }
+
+    fn write_be_i32(&mut self, data: i32) -> Result<()> {
+        self.write_all(&data.to_be_bytes())?;
+
+        Ok(())
+    }
+
+    fn write_be_i32_24(&mut self, data: i32) -> Result<()> {
+        let buf = data.to_be_bytes();
+        self.write_all(&[buf[1], buf[2], buf[3]])?;
+        
+        Ok(())
+    }
+
+    fn write_be_f32(&mut self, data: f32) -> Result<()> {
+        self.write_all(&data.to_be_bytes())?;
+        
+        Ok(())
+    }
+
+    fn write_be_f64(&mut self, data: f64) -> Result<()> {
+        self.write_all(&data.to_be_bytes())?;
+        
+        Ok(())
+    }
+}
+
+/// Outputs that can go back to fill in sizes, like the headers of finished files.
+pub trait LgSeekWriter: LgWriter {
+    fn go_to(&mut self, position: usize) -> Result<usize>;
+}
+impl<W: io::Write + io::Seek> LgSeekWriter for W {
+    fn go_to(&mut self, position: usize) -> Result<usize> {
+        Ok(self.seek(io::SeekFrom::Start(position as u64))? as usize)
+    }
+}
+
+/// Writes big endian values through the write_le_* methods, for code that only knows about little endian
+/// (like [`crate::Sample`]).
+pub(crate) struct BigEndian<'w, W>(pub(crate) &'w mut W);
+impl<W: LgWriter> LgWriter for BigEndian<'_, W> {
+    fn write_le_u8(&mut self, data: u8) -> Result<()> {
+        self.0.write_le_u8(data)
+    }
+
+    fn write_le_u16(&mut self, data: u16) -> Result<()> {
+        self.0.write_be_u16(data)
+    }
+
+    fn write_le_u32(&mut self, data: u32) -> Result<()> {
+        self.0.write_be_u32(data)
+    }
+
+    fn write_le_u64(&mut self, data: u64) -> Result<()> {
+        self.0.write_be_u64(data)
+    }
+
+    fn write_le_i8(&mut self, data: i8) -> Result<()> {
+        self.0.write_le_i8(data)
+    }
+
+    fn write_le_i16(&mut self, data: i16) -> Result<()> {
+        self.0.write_be_i16(data)
+    }
+
+    fn write_le_i32(&mut self, data: i32) -> Result<()> {
+        self.0.write_be_i32(data)
+    }
+
+    fn write_le_i32_24(&mut self, data: i32) -> Result<()> {
+        self.0.write_be_i32_24(data)
+    }
+
+    fn write_le_f32(&mut self, data: f32) -> Result<()> {
+        self.0.write_be_f32(data)
+    }
+
+    fn write_le_f64(&mut self, data: f64) -> Result<()> {
+        self.0.write_be_f64(data)
+    }
+
+    fn write_be_u16(&mut self, data: u16) -> Result<()> {
+        self.0.write_le_u16(data)
+    }
+
+    fn write_be_u32(&mut self, data: u32) -> Result<()> {
+        self.0.write_le_u32(data)
+    }
+
+    fn write_be_u64(&mut self, data: u64) -> Result<()> {
+        self.0.write_le_u64(data)
+    }
+
+    fn write_be_i16(&mut self, data: i16) -> Result<()> {
+        self.0.write_le_i16(data)
+    }
+
+    fn write_be_i32(&mut self, data: i32) -> Result<()> {
+        self.0.write_le_i32(data)
+    }
+
+    fn write_be_i32_24(&mut self, data: i32) -> Result<()> {
+        self.0.write_le_i32_24(data)
+    }
+
+    fn write_be_f32(&mut self, data: f32) -> Result<()> {
+        self.0.write_le_f32(data)
+    }
+
+    fn write_be_f64(&mut self, data: f64) -> Result<()> {
+        self.0.write_le_f64(data)
+    }
+}